@@ -0,0 +1,342 @@
+use super::AesError;
+
+// FIPS-197: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197.pdf
+pub const BLOCK_SIZE: usize = 16;
+
+pub trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block: &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block: &[u8]) -> Vec<u8>;
+}
+
+lazy_static! {
+    /**
+     * The S-box is built from its definition (multiplicative inverse in GF(2^8)
+     * followed by the affine transform, FIPS-197 section 5.1.1) rather than
+     * being written out as a table.
+     */
+    static ref SBOX: [u8; 256] = {
+        let mut sbox = [0u8; 256];
+        for (x, entry) in sbox.iter_mut().enumerate() {
+            let inv = gf_inverse(x as u8);
+            *entry = inv
+                ^ inv.rotate_left(1)
+                ^ inv.rotate_left(2)
+                ^ inv.rotate_left(3)
+                ^ inv.rotate_left(4)
+                ^ 0x63;
+        }
+        sbox
+    };
+    static ref INV_SBOX: [u8; 256] = {
+        let mut inv_sbox = [0u8; 256];
+        for (x, &s) in SBOX.iter().enumerate() {
+            inv_sbox[s as usize] = x as u8;
+        }
+        inv_sbox
+    };
+    // GF_MUL[c][a] == c * a for the small coefficients used by (Inv)MixColumns
+    static ref GF_MUL: [[u8; 256]; 16] = {
+        let mut table = [[0u8; 256]; 16];
+        for (c, row) in table.iter_mut().enumerate() {
+            for (a, entry) in row.iter_mut().enumerate() {
+                *entry = gf_mul(c as u8, a as u8);
+            }
+        }
+        table
+    };
+}
+
+fn xtime(a: u8) -> u8 {
+    if a & 0x80 != 0 {
+        (a << 1) ^ 0x1b
+    } else {
+        a << 1
+    }
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+// a^254 == a^-1 in GF(2^8), and maps 0 to 0 as the spec requires
+fn gf_inverse(a: u8) -> u8 {
+    let mut result = 1;
+    for _ in 0..254 {
+        result = gf_mul(result, a);
+    }
+    if a == 0 {
+        0
+    } else {
+        result
+    }
+}
+
+#[test]
+fn test_sbox() {
+    assert_eq!(SBOX[0x00], 0x63);
+    assert_eq!(SBOX[0x53], 0xed);
+    assert_eq!(SBOX[0xff], 0x16);
+    assert_eq!(INV_SBOX[0x63], 0x00);
+    assert_eq!(INV_SBOX[0xed], 0x53);
+}
+
+/**
+ * AES with a 128-, 192- or 256-bit key. The number of rounds is chosen from the key length.
+ * The state is kept as a flat 16-byte array in the column-major order of the spec, so
+ * state[row + 4 * column] is the byte at (row, column).
+ */
+#[derive(Clone)]
+pub struct Aes {
+    round_keys: Vec<[u8; BLOCK_SIZE]>,
+}
+
+impl Aes {
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        match key.len() {
+            16 | 24 | 32 => Ok(Aes {
+                round_keys: expand_key(key),
+            }),
+            len => Err(AesError::InvalidKeyLength(len)),
+        }
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+}
+
+// FIPS-197 section 5.2
+fn expand_key(key: &[u8]) -> Vec<[u8; BLOCK_SIZE]> {
+    let nk = key.len() / 4;
+    let nr = nk + 6;
+    let total_words = 4 * (nr + 1);
+
+    let mut words: Vec<[u8; 4]> = key.chunks(4).map(|w| [w[0], w[1], w[2], w[3]]).collect();
+    let mut rcon = 1u8;
+    for i in nk..total_words {
+        let mut temp = words[i - 1];
+        if i % nk == 0 {
+            temp.rotate_left(1);
+            temp = temp.map(|b| SBOX[b as usize]);
+            temp[0] ^= rcon;
+            rcon = xtime(rcon);
+        } else if nk > 6 && i % nk == 4 {
+            temp = temp.map(|b| SBOX[b as usize]);
+        }
+        let prev = words[i - nk];
+        words.push([
+            prev[0] ^ temp[0],
+            prev[1] ^ temp[1],
+            prev[2] ^ temp[2],
+            prev[3] ^ temp[3],
+        ]);
+    }
+
+    words
+        .chunks(4)
+        .map(|round| {
+            let mut round_key = [0u8; BLOCK_SIZE];
+            for (c, word) in round.iter().enumerate() {
+                round_key[4 * c..4 * c + 4].copy_from_slice(word);
+            }
+            round_key
+        })
+        .collect()
+}
+
+fn add_round_key(state: &mut [u8; BLOCK_SIZE], round_key: &[u8; BLOCK_SIZE]) {
+    for (s, k) in state.iter_mut().zip(round_key) {
+        *s ^= k;
+    }
+}
+
+fn sub_bytes(state: &mut [u8; BLOCK_SIZE]) {
+    let sbox = &*SBOX;
+    for b in state.iter_mut() {
+        *b = sbox[*b as usize];
+    }
+}
+
+fn inv_sub_bytes(state: &mut [u8; BLOCK_SIZE]) {
+    let inv_sbox = &*INV_SBOX;
+    for b in state.iter_mut() {
+        *b = inv_sbox[*b as usize];
+    }
+}
+
+// row r is rotated left by r columns
+fn shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    let old = *state;
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * c] = old[r + 4 * ((c + r) % 4)];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    let old = *state;
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * ((c + r) % 4)] = old[r + 4 * c];
+        }
+    }
+}
+
+// each output byte r is the dot product of the column with the coefficients rotated right by r
+fn mix_columns_with(state: &mut [u8; BLOCK_SIZE], [c0, c1, c2, c3]: [usize; 4]) {
+    let mul = &*GF_MUL;
+    for column in state.chunks_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]].map(usize::from);
+        column[0] = mul[c0][a0] ^ mul[c1][a1] ^ mul[c2][a2] ^ mul[c3][a3];
+        column[1] = mul[c3][a0] ^ mul[c0][a1] ^ mul[c1][a2] ^ mul[c2][a3];
+        column[2] = mul[c2][a0] ^ mul[c3][a1] ^ mul[c0][a2] ^ mul[c1][a3];
+        column[3] = mul[c1][a0] ^ mul[c2][a1] ^ mul[c3][a2] ^ mul[c0][a3];
+    }
+}
+
+fn mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    mix_columns_with(state, [0x02, 0x03, 0x01, 0x01]);
+}
+
+fn inv_mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    mix_columns_with(state, [0x0e, 0x0b, 0x0d, 0x09]);
+}
+
+fn to_state(block: &[u8]) -> [u8; BLOCK_SIZE] {
+    if block.len() != BLOCK_SIZE {
+        panic!(
+            "expected block of {} bytes, got {}",
+            BLOCK_SIZE,
+            block.len()
+        );
+    }
+    let mut state = [0u8; BLOCK_SIZE];
+    state.copy_from_slice(block);
+    state
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    // FIPS-197 section 5.1
+    fn encrypt_block(&self, block: &[u8]) -> Vec<u8> {
+        let mut state = to_state(block);
+        let nr = self.rounds();
+        add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1..nr {
+            sub_bytes(&mut state);
+            shift_rows(&mut state);
+            mix_columns(&mut state);
+            add_round_key(&mut state, &self.round_keys[round]);
+        }
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        add_round_key(&mut state, &self.round_keys[nr]);
+        state.to_vec()
+    }
+
+    // FIPS-197 section 5.3
+    fn decrypt_block(&self, block: &[u8]) -> Vec<u8> {
+        let mut state = to_state(block);
+        let nr = self.rounds();
+        add_round_key(&mut state, &self.round_keys[nr]);
+        for round in (1..nr).rev() {
+            inv_shift_rows(&mut state);
+            inv_sub_bytes(&mut state);
+            add_round_key(&mut state, &self.round_keys[round]);
+            inv_mix_columns(&mut state);
+        }
+        inv_shift_rows(&mut state);
+        inv_sub_bytes(&mut state);
+        add_round_key(&mut state, &self.round_keys[0]);
+        state.to_vec()
+    }
+}
+
+#[test]
+fn test_mix_columns() {
+    // https://en.wikipedia.org/wiki/Rijndael_MixColumns#Test_vectors_for_MixColumn()
+    let mut state = [
+        0xdb, 0x13, 0x53, 0x45, 0xf2, 0x0a, 0x22, 0x5c, 0x01, 0x01, 0x01, 0x01, 0xc6, 0xc6, 0xc6,
+        0xc6,
+    ];
+    let original = state;
+    mix_columns(&mut state);
+    assert_eq!(
+        state,
+        [
+            0x8e, 0x4d, 0xa1, 0xbc, 0x9f, 0xdc, 0x58, 0x9d, 0x01, 0x01, 0x01, 0x01, 0xc6, 0xc6,
+            0xc6, 0xc6
+        ]
+    );
+    inv_mix_columns(&mut state);
+    assert_eq!(state, original);
+}
+
+#[test]
+fn test_expand_key() {
+    // FIPS-197 appendix A.1
+    let key = crate::serializers::from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let round_keys = expand_key(&key);
+    assert_eq!(round_keys.len(), 11);
+    assert_eq!(
+        round_keys[10].to_vec(),
+        crate::serializers::from_hex("d014f9a8c9ee2589e13f0cc8b6630ca6").unwrap()
+    );
+}
+
+#[test]
+fn test_fips_197_vectors() {
+    use crate::serializers::from_hex;
+
+    // FIPS-197 appendix B, then appendix C.1, C.2 and C.3
+    let vectors = [
+        (
+            "2b7e151628aed2a6abf7158809cf4f3c",
+            "3243f6a8885a308d313198a2e0370734",
+            "3925841d02dc09fbdc118597196a0b32",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "00112233445566778899aabbccddeeff",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "00112233445566778899aabbccddeeff",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "00112233445566778899aabbccddeeff",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ),
+    ];
+    for (key, plaintext, ciphertext) in vectors {
+        let aes = Aes::new(&from_hex(key).unwrap()).unwrap();
+        let plaintext = from_hex(plaintext).unwrap();
+        let ciphertext = from_hex(ciphertext).unwrap();
+        assert_eq!(aes.encrypt_block(&plaintext), ciphertext);
+        assert_eq!(aes.decrypt_block(&ciphertext), plaintext);
+    }
+}
+
+#[test]
+fn test_invalid_key_length() {
+    assert!(matches!(
+        Aes::new(&[0; 17]),
+        Err(AesError::InvalidKeyLength(17))
+    ));
+}
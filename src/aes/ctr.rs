@@ -0,0 +1,262 @@
+use super::{block::BLOCK_SIZE, Aes, AesError, BlockCipher};
+use crate::xor::Xor;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/**
+ * Describes how the 16-byte counter block is built: `nonce_len` bytes of nonce
+ * followed by a block counter that fills the rest of the block.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CtrLayout {
+    pub nonce_len: usize,
+    pub counter_endianness: Endianness,
+}
+
+impl CtrLayout {
+    // 64-bit nonce, 64-bit little-endian block counter (challenge 18)
+    pub const CRYPTOPALS: CtrLayout = CtrLayout {
+        nonce_len: 8,
+        counter_endianness: Endianness::Little,
+    };
+
+    // 64-bit nonce, 64-bit big-endian block counter
+    pub const BIG_ENDIAN: CtrLayout = CtrLayout {
+        nonce_len: 8,
+        counter_endianness: Endianness::Big,
+    };
+
+    // 96-bit nonce, 32-bit big-endian block counter (as in NIST SP 800-38D)
+    pub const NONCE_96_COUNTER_32: CtrLayout = CtrLayout {
+        nonce_len: 12,
+        counter_endianness: Endianness::Big,
+    };
+
+    pub fn counter_len(&self) -> usize {
+        BLOCK_SIZE - self.nonce_len
+    }
+
+    /**
+     * The counter is a u64, truncated to counter_len() bytes, so a 32-bit
+     * counter wraps after 2^32 blocks.
+     */
+    fn counter_block(&self, nonce: &[u8], counter: u64) -> Vec<u8> {
+        let counter_len = self.counter_len();
+        let mut block = nonce.to_vec();
+        match self.counter_endianness {
+            Endianness::Little => block.extend_from_slice(&counter.to_le_bytes()[..counter_len]),
+            Endianness::Big => block.extend_from_slice(&counter.to_be_bytes()[8 - counter_len..]),
+        }
+        block
+    }
+}
+
+impl Default for CtrLayout {
+    fn default() -> Self {
+        CtrLayout::CRYPTOPALS
+    }
+}
+
+#[derive(Clone)]
+pub struct Ctr {
+    cipher: Aes,
+    nonce: Vec<u8>,
+    layout: CtrLayout,
+}
+
+impl Ctr {
+    pub fn new(key: &[u8], nonce: &[u8], layout: CtrLayout) -> Result<Self, AesError> {
+        if !(8..BLOCK_SIZE).contains(&layout.nonce_len) {
+            return Err(AesError::InvalidCtrLayout(layout.nonce_len));
+        }
+        if nonce.len() != layout.nonce_len {
+            return Err(AesError::InvalidNonceLength(nonce.len()));
+        }
+        Ok(Ctr {
+            cipher: Aes::new(key)?,
+            nonce: nonce.to_vec(),
+            layout,
+        })
+    }
+
+    pub fn keystream(&self) -> Keystream<'_> {
+        self.keystream_at(0)
+    }
+
+    /**
+     * Returns the keystream starting at byte `offset` of the stream, without
+     * generating any of the blocks before it.
+     */
+    pub fn keystream_at(&self, offset: usize) -> Keystream<'_> {
+        let counter = (offset / BLOCK_SIZE) as u64;
+        Keystream {
+            ctr: self,
+            counter: counter.wrapping_add(1),
+            block: self.keystream_block(counter),
+            pos: offset % BLOCK_SIZE,
+        }
+    }
+
+    fn keystream_block(&self, counter: u64) -> Vec<u8> {
+        self.cipher
+            .encrypt_block(&self.layout.counter_block(&self.nonce, counter))
+    }
+
+    // Encryption and decryption are the same operation
+    pub fn apply_keystream(&self, data: &[u8]) -> Vec<u8> {
+        self.apply_keystream_at(0, data)
+    }
+
+    pub fn apply_keystream_at(&self, offset: usize, data: &[u8]) -> Vec<u8> {
+        let keystream: Vec<u8> = self.keystream_at(offset).take(data.len()).collect();
+        data.xor(&keystream)
+    }
+
+    /**
+     * Replaces the plaintext at `offset` with `newtext`, re-encrypting only that part
+     * of the stream (challenge 25). The ciphertext grows if `newtext` runs past its end.
+     * Panics if `offset` is past the end of the ciphertext.
+     */
+    pub fn edit(&self, ciphertext: &[u8], offset: usize, newtext: &[u8]) -> Vec<u8> {
+        assert!(
+            offset <= ciphertext.len(),
+            "edit offset {} is past the end of the {}-byte ciphertext",
+            offset,
+            ciphertext.len()
+        );
+        let mut edited = ciphertext.to_vec();
+        let end = offset + newtext.len();
+        if end > edited.len() {
+            edited.resize(end, 0);
+        }
+        edited[offset..end].copy_from_slice(&self.apply_keystream_at(offset, newtext));
+        edited
+    }
+}
+
+pub struct Keystream<'a> {
+    ctr: &'a Ctr,
+    counter: u64,
+    block: Vec<u8>,
+    pos: usize,
+}
+
+impl Iterator for Keystream<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == BLOCK_SIZE {
+            self.block = self.ctr.keystream_block(self.counter);
+            self.counter = self.counter.wrapping_add(1);
+            self.pos = 0;
+        }
+        let byte = self.block[self.pos];
+        self.pos += 1;
+        Some(byte)
+    }
+}
+
+#[test]
+fn test_counter_block() {
+    let nonce = [0xaa; 8];
+    assert_eq!(
+        CtrLayout::CRYPTOPALS.counter_block(&nonce, 1),
+        [0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 1, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        CtrLayout::BIG_ENDIAN.counter_block(&nonce, 1),
+        [0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    let nonce = [0xbb; 12];
+    assert_eq!(
+        CtrLayout::NONCE_96_COUNTER_32.counter_block(&nonce, 0x0102),
+        [0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0xbb, 0, 0, 1, 2]
+    );
+}
+
+#[test]
+fn test_nist_sp800_38a_ctr() -> Result<(), AesError> {
+    use crate::serializers::from_hex;
+
+    // NIST SP 800-38A F.5.1, CTR-AES128.Encrypt. The initial counter block is
+    // f0f1...feff, read here as a 96-bit nonce followed by a big-endian counter
+    // that starts at 0xfcfdfeff, so start the keystream at that block.
+    let key = from_hex("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let nonce = from_hex("f0f1f2f3f4f5f6f7f8f9fafb").unwrap();
+    let ctr = Ctr::new(&key, &nonce, CtrLayout::NONCE_96_COUNTER_32)?;
+    let offset = 0xfcfdfeffusize * BLOCK_SIZE;
+    let plaintext =
+        from_hex("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51").unwrap();
+    let ciphertext =
+        from_hex("874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff").unwrap();
+    assert_eq!(ctr.apply_keystream_at(offset, &plaintext), ciphertext);
+    Ok(())
+}
+
+#[test]
+fn test_keystream_at() -> Result<(), AesError> {
+    let ctr = Ctr::new(b"YELLOW SUBMARINE", &[0; 8], CtrLayout::CRYPTOPALS)?;
+    let keystream: Vec<u8> = ctr.keystream().take(100).collect();
+    for offset in [0, 1, 15, 16, 17, 50] {
+        let partial: Vec<u8> = ctr.keystream_at(offset).take(100 - offset).collect();
+        assert_eq!(partial, keystream[offset..]);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_nonce() {
+    assert_eq!(
+        Ctr::new(b"YELLOW SUBMARINE", &[0; 12], CtrLayout::CRYPTOPALS).err(),
+        Some(AesError::InvalidNonceLength(12))
+    );
+}
+
+#[test]
+fn test_invalid_layout() {
+    for nonce_len in [4, 16] {
+        let layout = CtrLayout {
+            nonce_len,
+            ..CtrLayout::CRYPTOPALS
+        };
+        assert_eq!(
+            Ctr::new(b"YELLOW SUBMARINE", &vec![0; nonce_len], layout).err(),
+            Some(AesError::InvalidCtrLayout(nonce_len))
+        );
+    }
+}
+
+#[test]
+fn test_edit() -> Result<(), AesError> {
+    let ctr = Ctr::new(b"YELLOW SUBMARINE", &[0; 8], CtrLayout::CRYPTOPALS)?;
+    let ciphertext = ctr.apply_keystream(b"the quick brown fox jumps over the lazy dog");
+    let edited = ctr.edit(&ciphertext, 4, b"QUICK");
+    assert_eq!(
+        ctr.apply_keystream(&edited),
+        b"the QUICK brown fox jumps over the lazy dog"
+    );
+    let edited = ctr.edit(&ciphertext, 40, b"dogs!");
+    assert_eq!(
+        ctr.apply_keystream(&edited),
+        b"the quick brown fox jumps over the lazy dogs!"
+    );
+    // appending at exactly the end is allowed
+    let edited = ctr.edit(&ciphertext, ciphertext.len(), b"!");
+    assert_eq!(
+        ctr.apply_keystream(&edited),
+        b"the quick brown fox jumps over the lazy dog!"
+    );
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "past the end")]
+fn test_edit_past_end() {
+    let ctr = Ctr::new(b"YELLOW SUBMARINE", &[0; 8], CtrLayout::CRYPTOPALS).unwrap();
+    let ciphertext = ctr.apply_keystream(b"short");
+    ctr.edit(&ciphertext, 6, b"gap");
+}
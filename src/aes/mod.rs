@@ -0,0 +1,584 @@
+use crate::{mt19937, xor::Xor, MyResult};
+use rand::Rng;
+use std::{error, fmt, iter::once};
+
+pub mod block;
+pub mod ctr;
+
+pub use block::{Aes, BlockCipher, BLOCK_SIZE};
+pub use ctr::{Ctr, CtrLayout, Endianness};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Mode {
+    CBC,
+    ECB,
+    CTR(CtrLayout),
+    // not AES: a stream cipher keyed by the 16-bit (big-endian) seed of an MT19937 keystream
+    MT19937,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AesError {
+    IvNotAllowed,
+    IvRequired,
+    InvalidKeyLength(usize),
+    InvalidCiphertextLength(usize),
+    InvalidNonceLength(usize),
+    // a ctr layout whose nonce_len leaves less than 8 bytes or more than 15 for the nonce
+    InvalidCtrLayout(usize),
+    InvalidIvLength(usize),
+    // carries the decrypted plaintext, padding included, so callers can inspect it
+    Padding {
+        error: PaddingError,
+        plaintext: Vec<u8>,
+    },
+}
+
+impl error::Error for AesError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AesError::Padding { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AesError::IvNotAllowed => write!(f, "iv not allowed in this mode"),
+            AesError::IvRequired => write!(f, "iv must be supplied in this mode"),
+            AesError::InvalidKeyLength(len) => {
+                write!(f, "key len {} is not valid in this mode", len)
+            }
+            AesError::InvalidCiphertextLength(len) => {
+                write!(f, "ciphertext len {} is not a multiple of block size", len)
+            }
+            AesError::InvalidNonceLength(len) => {
+                write!(f, "nonce len {} does not match the ctr layout", len)
+            }
+            AesError::InvalidCtrLayout(nonce_len) => {
+                write!(
+                    f,
+                    "ctr layout nonce len {} is not between 8 and 15",
+                    nonce_len
+                )
+            }
+            AesError::InvalidIvLength(len) => {
+                write!(f, "iv len {} is not the block size", len)
+            }
+            AesError::Padding { error, .. } => write!(f, "invalid padding: {}", error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PaddingError {
+    EmptyInput,
+    ZeroPadByte,
+    PadLongerThanBlock(u8),
+    PadLongerThanInput(u8),
+    InconsistentPadBytes { expected: u8, found: u8 },
+}
+
+impl error::Error for PaddingError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaddingError::EmptyInput => write!(f, "cannot unpad empty input"),
+            PaddingError::ZeroPadByte => write!(f, "final pad byte cannot be zero"),
+            PaddingError::PadLongerThanBlock(last) => {
+                write!(f, "final pad byte {} is larger than block size", last)
+            }
+            PaddingError::PadLongerThanInput(last) => {
+                write!(f, "final pad byte {} is larger than input", last)
+            }
+            PaddingError::InconsistentPadBytes { expected, found } => {
+                write!(
+                    f,
+                    "bad pkcs7 padding byte: {}, should be: {}",
+                    found, expected
+                )
+            }
+        }
+    }
+}
+
+pub trait Decrypt {
+    fn decrypt(&self, mode: Mode, key: &[u8], iv: Option<&[u8]>) -> Result<Vec<u8>, AesError>;
+}
+
+impl Decrypt for [u8] {
+    fn decrypt(&self, mode: Mode, key: &[u8], iv: Option<&[u8]>) -> Result<Vec<u8>, AesError> {
+        match mode {
+            Mode::CBC => match iv {
+                Some(iv) => aes_cbc_decrypt(self, &Aes::new(key)?, iv),
+                None => Err(AesError::IvRequired),
+            },
+            Mode::ECB => match iv {
+                Some(_) => Err(AesError::IvNotAllowed),
+                None => aes_ecb_decrypt(self, &Aes::new(key)?),
+            },
+            Mode::CTR(layout) => match iv {
+                Some(nonce) => Ok(Ctr::new(key, nonce, layout)?.apply_keystream(self)),
+                None => Err(AesError::IvRequired),
+            },
+            Mode::MT19937 => match iv {
+                Some(_) => Err(AesError::IvNotAllowed),
+                None => mt19937_apply_keystream(self, key),
+            },
+        }
+    }
+}
+
+pub trait Encrypt {
+    fn encrypt(&self, mode: Mode, key: &[u8], iv: Option<&[u8]>) -> Result<Vec<u8>, AesError>;
+}
+
+impl Encrypt for [u8] {
+    fn encrypt(&self, mode: Mode, key: &[u8], iv: Option<&[u8]>) -> Result<Vec<u8>, AesError> {
+        match mode {
+            Mode::CBC => match iv {
+                Some(iv) => aes_cbc_encrypt(self, &Aes::new(key)?, iv),
+                None => Err(AesError::IvRequired),
+            },
+            Mode::ECB => match iv {
+                Some(_) => Err(AesError::IvNotAllowed),
+                None => Ok(aes_ecb_encrypt(self, &Aes::new(key)?)),
+            },
+            Mode::CTR(layout) => match iv {
+                Some(nonce) => Ok(Ctr::new(key, nonce, layout)?.apply_keystream(self)),
+                None => Err(AesError::IvRequired),
+            },
+            Mode::MT19937 => match iv {
+                Some(_) => Err(AesError::IvNotAllowed),
+                None => mt19937_apply_keystream(self, key),
+            },
+        }
+    }
+}
+
+fn mt19937_apply_keystream(data: &[u8], key: &[u8]) -> Result<Vec<u8>, AesError> {
+    let seed: [u8; 2] = key
+        .try_into()
+        .map_err(|_| AesError::InvalidKeyLength(key.len()))?;
+    Ok(mt19937::apply_keystream(u16::from_be_bytes(seed), data))
+}
+
+#[test]
+fn test_mt19937_roundtrip() {
+    let plaintext = b"a stream cipher keyed by a 16-bit seed";
+    let ciphertext = plaintext
+        .encrypt(Mode::MT19937, &[0x12, 0x34], None)
+        .unwrap();
+    assert_eq!(ciphertext, mt19937::apply_keystream(0x1234, plaintext));
+    assert_eq!(
+        ciphertext
+            .decrypt(Mode::MT19937, &[0x12, 0x34], None)
+            .unwrap(),
+        plaintext
+    );
+    assert_eq!(
+        plaintext.encrypt(Mode::MT19937, &[0; 16], None),
+        Err(AesError::InvalidKeyLength(16))
+    );
+    assert_eq!(
+        ciphertext.decrypt(Mode::MT19937, &[0], None),
+        Err(AesError::InvalidKeyLength(1))
+    );
+    assert_eq!(
+        plaintext.encrypt(Mode::MT19937, &[0; 2], Some(&[0; 16])),
+        Err(AesError::IvNotAllowed)
+    );
+    assert_eq!(
+        ciphertext.decrypt(Mode::MT19937, &[0; 2], Some(&[0; 16])),
+        Err(AesError::IvNotAllowed)
+    );
+}
+
+pub fn has_repeating_block(data: &[u8], size: usize) -> bool {
+    if !data.len().is_multiple_of(size) {
+        panic!("unexpected size of repeating block check");
+    }
+    let chunks = data.chunks(size).collect::<Vec<&[u8]>>();
+    let len = chunks.len();
+    for i in 0..len {
+        for j in (i + 1)..len {
+            if chunks[i] == chunks[j] {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+pub fn pkcs7_pad(input: &[u8], size: usize) -> Vec<u8> {
+    let rem_size = size - (input.len() - size * (input.len() / size));
+    let pad_len = if rem_size == 0 { size } else { rem_size };
+    let pad_byte = pad_len as u8;
+    let mut input = input.to_vec();
+    input.extend_from_slice(&vec![pad_byte; pad_len]);
+    input
+}
+
+#[test]
+fn test_pkcs7_pad() {
+    let input = b"YELLOW SUBMARINE";
+    let block = pkcs7_pad(input, 20);
+    assert_eq!(block, b"YELLOW SUBMARINE\x04\x04\x04\x04");
+
+    let input = b"YELLOW SUBMARINEYELLOW SUBMA";
+    let block = pkcs7_pad(input, 16);
+    assert_eq!(block, b"YELLOW SUBMARINEYELLOW SUBMA\x04\x04\x04\x04");
+
+    let input = b"YELLOW SUBMARINE";
+    let block = pkcs7_pad(input, 16);
+    let expected =
+        b"YELLOW SUBMARINE\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10";
+    assert_eq!(expected.len(), 32);
+    assert_eq!(block, expected);
+
+    let input = b"YELLOW SUB";
+    let block = pkcs7_pad(input, 16);
+    let expected = b"YELLOW SUB\x06\x06\x06\x06\x06\x06";
+    assert_eq!(expected.len(), 16);
+    assert_eq!(block, expected);
+}
+
+pub fn pkcs7_unpad(input: &[u8], block_size: usize) -> Result<Vec<u8>, PaddingError> {
+    let mut output = input.to_vec();
+    pkcs7_unpad_inplace(&mut output, block_size)?;
+    Ok(output)
+}
+
+pub fn pkcs7_unpad_inplace(input: &mut Vec<u8>, block_size: usize) -> Result<(), PaddingError> {
+    let last = *input.last().ok_or(PaddingError::EmptyInput)?;
+    if last == 0 {
+        return Err(PaddingError::ZeroPadByte);
+    }
+    if last as usize > block_size {
+        return Err(PaddingError::PadLongerThanBlock(last));
+    }
+    if last as usize > input.len() {
+        return Err(PaddingError::PadLongerThanInput(last));
+    }
+    for end_offset in 1..=last {
+        let byte = input[input.len() - end_offset as usize];
+        if byte != last {
+            return Err(PaddingError::InconsistentPadBytes {
+                expected: last,
+                found: byte,
+            });
+        }
+    }
+    let len = input.len();
+    input.truncate(len - last as usize);
+    Ok(())
+}
+
+#[test]
+fn test_pkcs7_unpad_inplace() -> Result<(), PaddingError> {
+    let input = b"YELLOW SUBMARINE".to_vec();
+    assert_eq!(input, pkcs7_unpad(&pkcs7_pad(&input, 16), 16)?);
+    assert_eq!(input, pkcs7_unpad(&pkcs7_pad(&input, 20), 20)?);
+
+    let input = b"YELLOW SUBMARINEYELLOW SUBMAR".to_vec();
+    assert_eq!(input, pkcs7_unpad(&pkcs7_pad(&input, 16), 16)?);
+    assert_eq!(input, pkcs7_unpad(&pkcs7_pad(&input, 20), 20)?);
+    Ok(())
+}
+
+#[test]
+fn test_pkcs7_unpad_errors() {
+    assert_eq!(pkcs7_unpad(b"", 16), Err(PaddingError::EmptyInput));
+    assert_eq!(
+        pkcs7_unpad(b"YELLOW SUBMARIN\x00", 16),
+        Err(PaddingError::ZeroPadByte)
+    );
+    assert_eq!(
+        pkcs7_unpad(b"YELLOW SUBMARIN\x11", 16),
+        Err(PaddingError::PadLongerThanBlock(0x11))
+    );
+    assert_eq!(
+        pkcs7_unpad(b"ICE\x05", 16),
+        Err(PaddingError::PadLongerThanInput(5))
+    );
+    assert_eq!(
+        pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+        Err(PaddingError::InconsistentPadBytes {
+            expected: 4,
+            found: 3
+        })
+    );
+}
+
+fn aes_ecb_encrypt(plaintext: &[u8], cipher: &impl BlockCipher) -> Vec<u8> {
+    let block_size = cipher.block_size();
+    pkcs7_pad(plaintext, block_size)
+        .chunks(block_size)
+        .flat_map(|block| cipher.encrypt_block(block))
+        .collect()
+}
+
+fn aes_ecb_decrypt(ciphertext: &[u8], cipher: &impl BlockCipher) -> Result<Vec<u8>, AesError> {
+    let block_size = cipher.block_size();
+    if !ciphertext.len().is_multiple_of(block_size) {
+        return Err(AesError::InvalidCiphertextLength(ciphertext.len()));
+    }
+    let plaintext: Vec<u8> = ciphertext
+        .chunks(block_size)
+        .flat_map(|block| cipher.decrypt_block(block))
+        .collect();
+    unpad_decrypted(plaintext, block_size)
+}
+
+fn unpad_decrypted(mut plaintext: Vec<u8>, block_size: usize) -> Result<Vec<u8>, AesError> {
+    match pkcs7_unpad_inplace(&mut plaintext, block_size) {
+        Ok(()) => Ok(plaintext),
+        Err(error) => Err(AesError::Padding { error, plaintext }),
+    }
+}
+
+fn aes_cbc_encrypt(
+    plaintext: &[u8],
+    cipher: &impl BlockCipher,
+    iv: &[u8],
+) -> Result<Vec<u8>, AesError> {
+    let block_size = cipher.block_size();
+    if iv.len() != block_size {
+        return Err(AesError::InvalidIvLength(iv.len()));
+    }
+    let mut ciphertext: Vec<u8> = Vec::new();
+    let mut prev = iv.to_vec();
+    for block in pkcs7_pad(plaintext, block_size).chunks(block_size) {
+        prev = cipher.encrypt_block(&block.xor(&prev));
+        ciphertext.extend_from_slice(&prev);
+    }
+    Ok(ciphertext)
+}
+
+fn aes_cbc_decrypt(
+    ciphertext: &[u8],
+    cipher: &impl BlockCipher,
+    iv: &[u8],
+) -> Result<Vec<u8>, AesError> {
+    let block_size = cipher.block_size();
+    if iv.len() != block_size {
+        return Err(AesError::InvalidIvLength(iv.len()));
+    }
+    if !ciphertext.len().is_multiple_of(block_size) {
+        return Err(AesError::InvalidCiphertextLength(ciphertext.len()));
+    }
+    let mut plaintext: Vec<u8> = Vec::new();
+    let ivs = once(iv).chain(ciphertext.chunks(block_size));
+    let blocks = ciphertext.chunks(block_size);
+    for (iv, block) in ivs.zip(blocks) {
+        let decrypted = cipher.decrypt_block(block);
+        plaintext.extend_from_slice(&decrypted.xor(iv));
+    }
+
+    unpad_decrypted(plaintext, block_size)
+}
+
+#[test]
+fn test_cbc_invalid_iv_length() {
+    let key = b"YELLOW SUBMARINE";
+    for iv in [&[0u8; 8][..], &[0; 17], &[]] {
+        assert_eq!(
+            b"YELLOW SUBMARINE".encrypt(Mode::CBC, key, Some(iv)),
+            Err(AesError::InvalidIvLength(iv.len()))
+        );
+        assert_eq!(
+            [0; 16].decrypt(Mode::CBC, key, Some(iv)),
+            Err(AesError::InvalidIvLength(iv.len()))
+        );
+    }
+}
+
+#[test]
+fn test_cbc_decrypt_tampered() -> Result<(), AesError> {
+    let key = b"YELLOW SUBMARINE";
+    let iv = [0; 16];
+    let mut ciphertext = b"YELLOW SUBMARINE".encrypt(Mode::CBC, key, Some(&iv))?;
+    // flipping the final byte of the first block changes the final pad byte to 0x11
+    ciphertext[15] ^= 0x10 ^ 0x11;
+    // the error carries the decrypted blocks, the tampered one garbled
+    match ciphertext.decrypt(Mode::CBC, key, Some(&iv)) {
+        Err(AesError::Padding { error, plaintext }) => {
+            assert_eq!(error, PaddingError::PadLongerThanBlock(0x11));
+            assert_eq!(plaintext.len(), 32);
+            assert_eq!(plaintext[16..31], [0x10; 15]);
+            assert_eq!(plaintext[31], 0x11);
+        }
+        res => panic!("expected a padding error, got {:?}", res),
+    }
+    assert_eq!(
+        ciphertext[..31].decrypt(Mode::CBC, key, Some(&iv)),
+        Err(AesError::InvalidCiphertextLength(31))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_roundtrip_key_sizes() -> MyResult<()> {
+    let plaintext = b"Cooking MC's like a pound of bacon";
+    let iv = [7; 16];
+    for key_len in [16, 24, 32] {
+        let key = random_bytes(key_len);
+        for (mode, iv) in [(Mode::ECB, None), (Mode::CBC, Some(&iv[..]))] {
+            let ciphertext = plaintext.encrypt(mode, &key, iv)?;
+            assert_eq!(ciphertext.len(), 48);
+            assert_eq!(ciphertext.decrypt(mode, &key, iv)?, plaintext);
+        }
+    }
+    Ok(())
+}
+
+pub struct Oracle {
+    mode: Mode,
+    ciphertext: Vec<u8>,
+}
+
+pub(crate) fn random_bytes(len: usize) -> Vec<u8> {
+    random_bytes_with(&mut rand::thread_rng(), len)
+}
+
+fn random_bytes_range_with(rng: &mut impl Rng, min: usize, max: usize) -> Vec<u8> {
+    let len = rng.gen_range(min..(max + 1));
+    random_bytes_with(rng, len)
+}
+
+fn random_bytes_with(rng: &mut impl Rng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+impl Oracle {
+    pub fn new(input: &[u8]) -> MyResult<Self> {
+        Oracle::with_rng(input, &mut rand::thread_rng())
+    }
+
+    /**
+     * All of the oracle's choices come from `rng`, so a seeded generator such as
+     * `Mt19937` makes it reproducible.
+     */
+    pub fn with_rng(input: &[u8], rng: &mut impl Rng) -> MyResult<Self> {
+        let mode = if rng.gen() { Mode::CBC } else { Mode::ECB };
+        let key = random_bytes_with(rng, BLOCK_SIZE);
+        let mut plaintext = random_bytes_range_with(rng, 5, 10);
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(&random_bytes_range_with(rng, 5, 10));
+
+        let ciphertext = match mode {
+            Mode::CBC => {
+                let iv = random_bytes_with(rng, BLOCK_SIZE);
+                plaintext.encrypt(mode, &key, Some(&iv))?
+            }
+            Mode::ECB => plaintext.encrypt(mode, &key, None)?,
+            Mode::CTR(_) | Mode::MT19937 => {
+                unreachable!("oracle only chooses between CBC and ECB")
+            }
+        };
+
+        Ok(Oracle { mode, ciphertext })
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext
+    }
+
+    pub fn is_cbc(&self) -> bool {
+        self.mode == Mode::CBC
+    }
+
+    pub fn is_ecb(&self) -> bool {
+        self.mode == Mode::ECB
+    }
+
+    pub fn verify(&self, is_ecb: bool) -> MyResult<()> {
+        match (is_ecb, self.mode == Mode::ECB) {
+            (true, true) => Ok(()),
+            (false, true) => Err("Incorrectly detected CBC".into()),
+            (false, false) => Ok(()),
+            (true, false) => Err("Incorrectly detected ECB".into()),
+        }
+    }
+}
+
+pub fn detect_ecb11(ciphertext: &[u8]) -> bool {
+    let blocks: Vec<&[u8]> = ciphertext.chunks(BLOCK_SIZE).skip(1).take(2).collect();
+    blocks[0] == blocks[1]
+}
+
+#[test]
+fn test_seeded_oracles() {
+    use crate::mt19937::Mt19937;
+
+    let input = [0; 16 * 3];
+    let l = Oracle::with_rng(&input, &mut Mt19937::new(11)).unwrap();
+    let r = Oracle::with_rng(&input, &mut Mt19937::new(11)).unwrap();
+    assert_eq!(l.ciphertext(), r.ciphertext());
+    assert_eq!(l.is_ecb(), r.is_ecb());
+
+    let l = SuffixOracle::with_random_prefix_and_rng(b"suffix", &mut Mt19937::new(12));
+    let r = SuffixOracle::with_random_prefix_and_rng(b"suffix", &mut Mt19937::new(12));
+    assert_eq!(l.encrypt(b"input"), r.encrypt(b"input"));
+}
+
+/**
+ * Encrypts `prefix || input || suffix` under a fixed random key in ECB mode.
+ * The prefix is empty for challenge 12 and a random number of random bytes
+ * for challenge 14.
+ */
+pub struct SuffixOracle {
+    key: Vec<u8>,
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl SuffixOracle {
+    pub fn new(suffix: &[u8]) -> Self {
+        SuffixOracle::with_rng(suffix, &mut rand::thread_rng())
+    }
+
+    pub fn with_rng(suffix: &[u8], rng: &mut impl Rng) -> Self {
+        SuffixOracle {
+            key: random_bytes_with(rng, BLOCK_SIZE),
+            prefix: Vec::new(),
+            suffix: suffix.to_vec(),
+        }
+    }
+
+    pub fn with_random_prefix(suffix: &[u8]) -> Self {
+        SuffixOracle::with_random_prefix_and_rng(suffix, &mut rand::thread_rng())
+    }
+
+    pub fn with_random_prefix_and_rng(suffix: &[u8], rng: &mut impl Rng) -> Self {
+        let oracle = SuffixOracle::with_rng(suffix, rng);
+        SuffixOracle {
+            prefix: random_bytes_range_with(rng, 0, 3 * BLOCK_SIZE),
+            ..oracle
+        }
+    }
+
+    pub fn encrypt(&self, input: &[u8]) -> Vec<u8> {
+        let mut plaintext = self.prefix.clone();
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(&self.suffix);
+        plaintext
+            .encrypt(Mode::ECB, &self.key, None)
+            .expect("oracle key is always valid")
+    }
+
+    pub fn verify(&self, recovered: &[u8]) -> MyResult<()> {
+        if recovered == self.suffix {
+            Ok(())
+        } else {
+            Err("Recovered suffix does not match".into())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    aes::{random_bytes, AesError, CtrLayout, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    xor::Xor,
+};
+
+pub const COMMENT_PREFIX: &[u8] = b"comment1=cooking%20MCs;userdata=";
+pub const COMMENT_SUFFIX: &[u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+const ADMIN: &[u8] = b";admin=true;";
+
+/**
+ * Wraps user data in the comment string and encrypts it, after quoting out `;` and
+ * `=` so the data can't add pairs of its own (challenges 16 and 26).
+ */
+pub struct CommentOracle {
+    mode: Mode,
+    key: Vec<u8>,
+    iv: Vec<u8>,
+}
+
+impl CommentOracle {
+    pub fn new_cbc() -> Self {
+        CommentOracle {
+            mode: Mode::CBC,
+            key: random_bytes(BLOCK_SIZE),
+            iv: random_bytes(BLOCK_SIZE),
+        }
+    }
+
+    pub fn new_ctr(layout: CtrLayout) -> Self {
+        CommentOracle {
+            mode: Mode::CTR(layout),
+            key: random_bytes(BLOCK_SIZE),
+            iv: random_bytes(layout.nonce_len),
+        }
+    }
+
+    pub fn encrypt(&self, userdata: &[u8]) -> Vec<u8> {
+        let mut plaintext = COMMENT_PREFIX.to_vec();
+        for &byte in userdata {
+            match byte {
+                b';' => plaintext.extend_from_slice(b"%3B"),
+                b'=' => plaintext.extend_from_slice(b"%3D"),
+                _ => plaintext.push(byte),
+            }
+        }
+        plaintext.extend_from_slice(COMMENT_SUFFIX);
+        plaintext
+            .encrypt(self.mode, &self.key, Some(&self.iv))
+            .expect("oracle key and iv are always valid")
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, AesError> {
+        ciphertext.decrypt(self.mode, &self.key, Some(&self.iv))
+    }
+
+    pub fn is_admin(&self, ciphertext: &[u8]) -> Result<bool, AesError> {
+        Ok(self
+            .decrypt(ciphertext)?
+            .split(|&b| b == b';')
+            .any(|pair| pair == b"admin=true"))
+    }
+}
+
+/**
+ * Returns `ciphertext` with the bits flipped that turn `known` into `desired` at
+ * `offset`. In CTR mode this changes the plaintext at the same offset; in CBC mode it
+ * changes the plaintext one block later (and scrambles the block it lands in).
+ */
+pub fn flip_bits(ciphertext: &[u8], offset: usize, known: &[u8], desired: &[u8]) -> Vec<u8> {
+    let delta = known.xor(desired);
+    let end = offset + delta.len();
+    let mut flipped = ciphertext.to_vec();
+    flipped[offset..end].copy_from_slice(&ciphertext[offset..end].xor(&delta));
+    flipped
+}
+
+/**
+ * Challenge 16: sacrifice a block of filler in front of a known block, and flip bits
+ * in the filler's ciphertext to rewrite the known block as `;admin=true;`.
+ */
+pub fn cbc_forge_admin(encrypt: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let align_len = (BLOCK_SIZE - COMMENT_PREFIX.len() % BLOCK_SIZE) % BLOCK_SIZE;
+    let target_offset = COMMENT_PREFIX.len() + align_len + BLOCK_SIZE;
+    let known = vec![b'A'; ADMIN.len()];
+
+    let mut userdata = vec![b'A'; align_len + BLOCK_SIZE];
+    userdata.extend_from_slice(&known);
+    let ciphertext = encrypt(&userdata);
+    flip_bits(&ciphertext, target_offset - BLOCK_SIZE, &known, ADMIN)
+}
+
+/**
+ * Challenge 26: CTR has no diffusion at all, so the bits are flipped directly over
+ * the known user data.
+ */
+pub fn ctr_forge_admin(encrypt: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let known = vec![b'A'; ADMIN.len()];
+    let ciphertext = encrypt(&known);
+    flip_bits(&ciphertext, COMMENT_PREFIX.len(), &known, ADMIN)
+}
+
+#[test]
+fn test_comment_oracle_quotes() -> Result<(), AesError> {
+    let oracle = CommentOracle::new_cbc();
+    let ciphertext = oracle.encrypt(b";admin=true;");
+    assert!(!oracle.is_admin(&ciphertext)?);
+    let plaintext = oracle.decrypt(&ciphertext)?;
+    assert_eq!(
+        plaintext,
+        b"comment1=cooking%20MCs;userdata=%3Badmin%3Dtrue%3B;comment2=%20like%20a%20pound%20of%20bacon"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_flip_bits() {
+    assert_eq!(flip_bits(&[0, 0, 0, 0], 1, b"ab", b"ac"), [0, 0, 1, 0]);
+}
@@ -0,0 +1,147 @@
+use crate::{
+    aes::{random_bytes, AesError, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    xor::Xor,
+    MyResult,
+};
+use std::{error, fmt};
+
+#[derive(Debug, PartialEq)]
+pub enum KeyAsIvError {
+    Aes(AesError),
+    // the receiver complains about the bad plaintext by echoing it back
+    HighAscii(Vec<u8>),
+}
+
+impl error::Error for KeyAsIvError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            KeyAsIvError::Aes(e) => Some(e),
+            KeyAsIvError::HighAscii(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyAsIvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyAsIvError::Aes(e) => write!(f, "{}", e),
+            KeyAsIvError::HighAscii(plaintext) => write!(
+                f,
+                "plaintext is not ascii: {}",
+                String::from_utf8_lossy(plaintext)
+            ),
+        }
+    }
+}
+
+impl From<AesError> for KeyAsIvError {
+    fn from(e: AesError) -> Self {
+        KeyAsIvError::Aes(e)
+    }
+}
+
+/**
+ * Encrypts under CBC, reusing the key as the IV (challenge 27). Decrypted plaintexts
+ * are checked for high-ascii bytes before the padding is, and rejected plaintexts are
+ * returned in the error.
+ */
+pub struct KeyAsIvOracle {
+    key: Vec<u8>,
+}
+
+impl KeyAsIvOracle {
+    pub fn new() -> Self {
+        KeyAsIvOracle {
+            key: random_bytes(BLOCK_SIZE),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        plaintext
+            .encrypt(Mode::CBC, &self.key, Some(&self.key))
+            .expect("oracle key is always valid")
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, KeyAsIvError> {
+        let plaintext = match ciphertext.decrypt(Mode::CBC, &self.key, Some(&self.key)) {
+            Ok(plaintext) => plaintext,
+            Err(AesError::Padding { plaintext, .. }) if !plaintext.is_ascii() => {
+                return Err(KeyAsIvError::HighAscii(plaintext))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if !plaintext.is_ascii() {
+            return Err(KeyAsIvError::HighAscii(plaintext));
+        }
+        Ok(plaintext)
+    }
+
+    pub fn verify(&self, key: &[u8]) -> MyResult<()> {
+        if key != self.key {
+            return Err("recovered key does not match".into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for KeyAsIvOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * With C1||0||C1, the first plaintext block is D(C1) ^ key and the third is D(C1) ^ 0,
+ * so xoring them gives the key.
+ */
+pub fn recover_key(
+    ciphertext: &[u8],
+    decrypt: impl Fn(&[u8]) -> Result<Vec<u8>, KeyAsIvError>,
+) -> MyResult<Vec<u8>> {
+    let c1 = ciphertext
+        .get(..BLOCK_SIZE)
+        .ok_or("ciphertext must be at least one block")?;
+    let mut modified = c1.to_vec();
+    modified.extend_from_slice(&[0; BLOCK_SIZE]);
+    modified.extend_from_slice(c1);
+    match decrypt(&modified) {
+        Err(KeyAsIvError::HighAscii(mut plaintext)) => {
+            // the third block can happen to end in valid padding, which gets stripped
+            let pad = 3 * BLOCK_SIZE - plaintext.len();
+            plaintext.resize(3 * BLOCK_SIZE, pad as u8);
+            let p1 = &plaintext[..BLOCK_SIZE];
+            let p3 = &plaintext[2 * BLOCK_SIZE..3 * BLOCK_SIZE];
+            Ok(p1.xor(p3))
+        }
+        Err(e) => Err(e.into()),
+        Ok(_) => Err("oracle accepted the modified ciphertext".into()),
+    }
+}
+
+#[test]
+fn test_rejects_high_ascii() {
+    let oracle = KeyAsIvOracle::new();
+    let plaintext = b"caf\xc3\xa9";
+    assert_eq!(
+        oracle.decrypt(&oracle.encrypt(plaintext)),
+        Err(KeyAsIvError::HighAscii(plaintext.to_vec()))
+    );
+    assert_eq!(
+        oracle.decrypt(&oracle.encrypt(b"cafe")),
+        Ok(b"cafe".to_vec())
+    );
+}
+
+#[test]
+fn test_recover_key_stripped_padding() -> MyResult<()> {
+    let key = [0x07; BLOCK_SIZE];
+    let p3 = [0x01; BLOCK_SIZE];
+    let mut plaintext = p3.xor(&key);
+    plaintext.extend_from_slice(&[0x80; BLOCK_SIZE]);
+    plaintext.extend_from_slice(&p3[..BLOCK_SIZE - 1]);
+    let recovered = recover_key(&[0; BLOCK_SIZE], |_| {
+        Err(KeyAsIvError::HighAscii(plaintext.clone()))
+    })?;
+    assert_eq!(recovered, key);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::{
+    aes::{random_bytes, Ctr, CtrLayout, BLOCK_SIZE},
+    xor::Xor,
+};
+
+/**
+ * Holds a CTR ciphertext under a random key and nonce, and exposes an edit function
+ * that lets the caller overwrite any part of the plaintext (challenge 25).
+ */
+pub struct EditOracle {
+    ctr: Ctr,
+    ciphertext: Vec<u8>,
+}
+
+impl EditOracle {
+    pub fn new(plaintext: &[u8]) -> Self {
+        let layout = CtrLayout::CRYPTOPALS;
+        let ctr = Ctr::new(
+            &random_bytes(BLOCK_SIZE),
+            &random_bytes(layout.nonce_len),
+            layout,
+        )
+        .expect("random key and nonce are always valid");
+        let ciphertext = ctr.apply_keystream(plaintext);
+        EditOracle { ctr, ciphertext }
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext
+    }
+
+    pub fn edit(&self, ciphertext: &[u8], offset: usize, newtext: &[u8]) -> Vec<u8> {
+        self.ctr.edit(ciphertext, offset, newtext)
+    }
+}
+
+/**
+ * Editing the whole plaintext to zeros makes the oracle hand back the keystream itself,
+ * which decrypts the original ciphertext.
+ */
+pub fn recover_plaintext(
+    ciphertext: &[u8],
+    edit: impl Fn(&[u8], usize, &[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    let keystream = edit(ciphertext, 0, &vec![0; ciphertext.len()]);
+    ciphertext.xor(&keystream)
+}
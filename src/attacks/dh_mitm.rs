@@ -0,0 +1,324 @@
+use crate::{
+    bigint::BigUint,
+    dh::{decrypt_message, derive_key, encrypt_message, DhParams, Keypair},
+    MyResult,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    // A->B: p, g, A all at once (challenge 34)
+    Params { params: DhParams, public: BigUint },
+    // A->B: p, g, and B->A: ACK, before the public keys are exchanged (challenge 35)
+    Negotiate(DhParams),
+    Ack(DhParams),
+    Public(BigUint),
+    // AES-CBC(SHA1(s)[0:16], iv=random(16), msg) + iv
+    Ciphertext(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Alice,
+    Bob,
+}
+
+/**
+ * Starts the exchange, sends her message once there's a key, and checks that Bob
+ * echoes it back
+ */
+pub struct Alice {
+    message: Vec<u8>,
+    params: DhParams,
+    keypair: Option<Keypair>,
+    key: Option<Vec<u8>>,
+    echo: Option<Vec<u8>>,
+}
+
+impl Alice {
+    pub fn new(params: DhParams, message: &[u8]) -> Self {
+        Alice {
+            message: message.to_vec(),
+            params,
+            keypair: None,
+            key: None,
+            echo: None,
+        }
+    }
+
+    pub fn start(&mut self, negotiate: bool) -> Message {
+        if negotiate {
+            return Message::Negotiate(self.params.clone());
+        }
+        let keypair = Keypair::generate(&self.params);
+        let public = keypair.public.clone();
+        self.keypair = Some(keypair);
+        Message::Params {
+            params: self.params.clone(),
+            public,
+        }
+    }
+
+    pub fn receive(&mut self, message: Message) -> MyResult<Option<Message>> {
+        match message {
+            Message::Ack(params) => {
+                self.params = params;
+                let keypair = Keypair::generate(&self.params);
+                let public = keypair.public.clone();
+                self.keypair = Some(keypair);
+                Ok(Some(Message::Public(public)))
+            }
+            Message::Public(public) => {
+                let keypair = self.keypair.as_ref().ok_or("alice has no keypair yet")?;
+                let key = derive_key(&keypair.shared_secret(&self.params, &public));
+                let ciphertext = encrypt_message(&key, &self.message)?;
+                self.key = Some(key);
+                Ok(Some(Message::Ciphertext(ciphertext)))
+            }
+            Message::Ciphertext(ciphertext) => {
+                let key = self.key.as_ref().ok_or("alice has no key yet")?;
+                self.echo = Some(decrypt_message(key, &ciphertext)?);
+                Ok(None)
+            }
+            message => Err(format!("alice did not expect {:?}", message).into()),
+        }
+    }
+
+    pub fn echo(&self) -> Option<&[u8]> {
+        self.echo.as_deref()
+    }
+}
+
+/**
+ * Goes along with whatever group he's given and echoes back whatever he receives
+ */
+#[derive(Default)]
+pub struct Bob {
+    params: Option<DhParams>,
+    key: Option<Vec<u8>>,
+    received: Vec<Vec<u8>>,
+}
+
+impl Bob {
+    pub fn new() -> Self {
+        Bob::default()
+    }
+
+    fn agree(&mut self, params: DhParams, public: &BigUint) -> Message {
+        let keypair = Keypair::generate(&params);
+        self.key = Some(derive_key(&keypair.shared_secret(&params, public)));
+        self.params = Some(params);
+        Message::Public(keypair.public)
+    }
+
+    pub fn receive(&mut self, message: Message) -> MyResult<Option<Message>> {
+        match message {
+            Message::Params { params, public } => Ok(Some(self.agree(params, &public))),
+            Message::Negotiate(params) => {
+                self.params = Some(params.clone());
+                Ok(Some(Message::Ack(params)))
+            }
+            Message::Public(public) => {
+                let params = self.params.clone().ok_or("bob has no group yet")?;
+                Ok(Some(self.agree(params, &public)))
+            }
+            Message::Ciphertext(ciphertext) => {
+                let key = self.key.as_ref().ok_or("bob has no key yet")?;
+                let plaintext = decrypt_message(key, &ciphertext)?;
+                let echo = encrypt_message(key, &plaintext)?;
+                self.received.push(plaintext);
+                Ok(Some(Message::Ciphertext(echo)))
+            }
+            message => Err(format!("bob did not expect {:?}", message).into()),
+        }
+    }
+
+    pub fn received(&self) -> &[Vec<u8>] {
+        &self.received
+    }
+}
+
+/**
+ * Sits between Alice and Bob and sees, and may replace, every message on its way to `to`
+ */
+pub trait Interceptor {
+    fn intercept(&mut self, to: Role, message: Message) -> Message;
+}
+
+/**
+ * Passes every message through untouched
+ */
+pub struct NoMitm;
+
+impl Interceptor for NoMitm {
+    fn intercept(&mut self, _to: Role, message: Message) -> Message {
+        message
+    }
+}
+
+/**
+ * Passes messages between Alice and Bob through `interceptor` until neither has
+ * anything more to say
+ */
+pub fn run_exchange(
+    alice: &mut Alice,
+    bob: &mut Bob,
+    interceptor: &mut impl Interceptor,
+    negotiate: bool,
+) -> MyResult<()> {
+    let mut message = alice.start(negotiate);
+    let mut to = Role::Bob;
+    loop {
+        let message_in = interceptor.intercept(to, message);
+        let reply = match to {
+            Role::Alice => alice.receive(message_in)?,
+            Role::Bob => bob.receive(message_in)?,
+        };
+        match reply {
+            Some(reply) => message = reply,
+            None => return Ok(()),
+        }
+        to = match to {
+            Role::Alice => Role::Bob,
+            Role::Bob => Role::Alice,
+        };
+    }
+}
+
+/**
+ * Replaces both public keys with p (challenge 34). Each side then computes p^x mod p,
+ * so the shared secret is always 0 and every message can be read in transit.
+ */
+#[derive(Default)]
+pub struct ParameterInjection {
+    p: Option<BigUint>,
+    pub recovered: Vec<Vec<u8>>,
+}
+
+impl ParameterInjection {
+    pub fn new() -> Self {
+        ParameterInjection::default()
+    }
+}
+
+impl Interceptor for ParameterInjection {
+    fn intercept(&mut self, _to: Role, message: Message) -> Message {
+        match message {
+            Message::Params { params, .. } => {
+                self.p = Some(params.p.clone());
+                Message::Params {
+                    public: params.p.clone(),
+                    params,
+                }
+            }
+            Message::Public(public) => Message::Public(self.p.clone().unwrap_or(public)),
+            Message::Ciphertext(ciphertext) => {
+                let key = derive_key(&BigUint::zero());
+                if let Ok(plaintext) = decrypt_message(&key, &ciphertext) {
+                    self.recovered.push(plaintext);
+                }
+                Message::Ciphertext(ciphertext)
+            }
+            message => message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaliciousG {
+    One,
+    P,
+    PMinusOne,
+}
+
+/**
+ * Replaces g in the group negotiation (challenge 35), which pins the shared secret
+ * to a value that's known without either private key:
+ *  - g = 1: every power of g is 1
+ *  - g = p: every power of g is 0 mod p
+ *  - g = p - 1: powers are 1 or p - 1, and the secret is p - 1 only when both
+ *    public keys are
+ */
+pub struct MaliciousGroup {
+    g: MaliciousG,
+    p: Option<BigUint>,
+    publics: Vec<BigUint>,
+    pub recovered: Vec<Vec<u8>>,
+}
+
+impl MaliciousGroup {
+    pub fn new(g: MaliciousG) -> Self {
+        MaliciousGroup {
+            g,
+            p: None,
+            publics: Vec::new(),
+            recovered: Vec::new(),
+        }
+    }
+
+    fn tamper(&mut self, params: DhParams) -> DhParams {
+        let one = BigUint::one();
+        let g = match self.g {
+            MaliciousG::One => one,
+            MaliciousG::P => params.p.clone(),
+            MaliciousG::PMinusOne => &params.p - &one,
+        };
+        self.p = Some(params.p.clone());
+        DhParams { p: params.p, g }
+    }
+
+    /**
+     * None until a group has been tampered with, since without a negotiation the
+     * secret is whatever Alice and Bob agreed on honestly
+     */
+    fn secret(&self) -> Option<BigUint> {
+        let p = self.p.as_ref()?;
+        Some(match self.g {
+            MaliciousG::One => BigUint::one(),
+            MaliciousG::P => BigUint::zero(),
+            MaliciousG::PMinusOne => {
+                let p_minus_1 = p - &BigUint::one();
+                if self.publics.len() == 2 && self.publics.iter().all(|public| public == &p_minus_1)
+                {
+                    p_minus_1
+                } else {
+                    BigUint::one()
+                }
+            }
+        })
+    }
+}
+
+impl Interceptor for MaliciousGroup {
+    fn intercept(&mut self, _to: Role, message: Message) -> Message {
+        match message {
+            Message::Negotiate(params) => Message::Negotiate(self.tamper(params)),
+            Message::Ack(params) => Message::Ack(self.tamper(params)),
+            Message::Public(public) => {
+                self.publics.push(public.clone());
+                Message::Public(public)
+            }
+            Message::Ciphertext(ciphertext) => {
+                if let Some(secret) = self.secret() {
+                    if let Ok(plaintext) = decrypt_message(&derive_key(&secret), &ciphertext) {
+                        self.recovered.push(plaintext);
+                    }
+                }
+                Message::Ciphertext(ciphertext)
+            }
+            message => message,
+        }
+    }
+}
+
+#[test]
+fn test_malicious_group_without_negotiation() -> MyResult<()> {
+    let message = b"no group to tamper with";
+    for g in [MaliciousG::One, MaliciousG::P, MaliciousG::PMinusOne] {
+        let (mut alice, mut bob) = (Alice::new(DhParams::nist(), message), Bob::new());
+        let mut mitm = MaliciousGroup::new(g);
+        run_exchange(&mut alice, &mut bob, &mut mitm, false)?;
+        assert_eq!(alice.echo(), Some(&message[..]));
+        assert!(mitm.recovered.is_empty());
+    }
+    Ok(())
+}
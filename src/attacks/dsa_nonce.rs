@@ -0,0 +1,141 @@
+use crate::{
+    bigint::BigUint,
+    dsa::{private_key_from_nonce, DsaParams, Signature},
+    MyResult,
+};
+use std::fmt;
+
+/**
+ * Finds the private key behind a signature whose nonce is below `max_k` (challenge 43).
+ * g^k is stepped along one multiplication at a time, and only a k that reproduces r
+ * is turned into a key and checked against the public key.
+ */
+pub fn recover_key_from_small_nonce(
+    params: &DsaParams,
+    public: &BigUint,
+    hash: &BigUint,
+    signature: &Signature,
+    max_k: u32,
+) -> Option<BigUint> {
+    let mut g_k = BigUint::one();
+    for k in 1..=max_k {
+        g_k = &(&g_k * &params.g) % &params.p;
+        if &g_k % &params.q != signature.r {
+            continue;
+        }
+        let x = private_key_from_nonce(params, hash, signature, &BigUint::from(k))?;
+        if &params.public_key(&x) == public {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/**
+ * A signed message as listed in challenge 44: the message, its signature and its
+ * SHA-1 as a number
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMessage {
+    pub msg: String,
+    pub signature: Signature,
+    pub m: BigUint,
+}
+
+/**
+ * Formats as the four lines the challenge uses, with s and r in decimal and m in hex
+ */
+impl fmt::Display for SignedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "msg: {}", self.msg)?;
+        writeln!(f, "s: {}", self.signature.s)?;
+        writeln!(f, "r: {}", self.signature.r)?;
+        writeln!(f, "m: {}", self.m.to_hex())
+    }
+}
+
+/**
+ * Parses groups of `msg:`, `s:`, `r:` and `m:` lines. The message is kept exactly as
+ * written, including trailing spaces, since they're part of what was hashed.
+ */
+pub fn parse_signed_messages(text: &str) -> MyResult<Vec<SignedMessage>> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if !lines.len().is_multiple_of(4) {
+        return Err("signed messages come in groups of four lines".into());
+    }
+    lines
+        .chunks(4)
+        .map(|chunk| {
+            let field = |idx: usize, name: &str| -> MyResult<&str> {
+                chunk[idx]
+                    .strip_prefix(name)
+                    .and_then(|line| line.strip_prefix(": "))
+                    .ok_or_else(|| format!("expected {}: in {:?}", name, chunk[idx]).into())
+            };
+            Ok(SignedMessage {
+                msg: field(0, "msg")?.to_string(),
+                signature: Signature {
+                    s: BigUint::from_dec(field(1, "s")?)?,
+                    r: BigUint::from_dec(field(2, "r")?)?,
+                },
+                m: BigUint::from_hex(field(3, "m")?)?,
+            })
+        })
+        .collect()
+}
+
+/**
+ * Pairs of messages signed with the same nonce, which shows up as the same r
+ */
+pub fn find_repeated_nonces(messages: &[SignedMessage]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in messages.iter().enumerate() {
+        for (j, b) in messages.iter().enumerate().skip(i + 1) {
+            if a.signature.r == b.signature.r {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/**
+ * Recovers the private key from two messages signed with the same nonce
+ * (challenge 44): s1 - s2 = k^-1 (m1 - m2) mod q, so k = (m1 - m2) / (s1 - s2).
+ */
+pub fn recover_key_from_repeated_nonce(
+    params: &DsaParams,
+    a: &SignedMessage,
+    b: &SignedMessage,
+) -> Option<BigUint> {
+    let q = &params.q;
+    let sub_mod = |l: &BigUint, r: &BigUint| &(&(l % q) + &(q - &(r % q))) % q;
+    let ds = sub_mod(&a.signature.s, &b.signature.s);
+    let k = &(&sub_mod(&a.m, &b.m) * &ds.modinv(q)?) % q;
+    private_key_from_nonce(params, &a.m, &a.signature, &k)
+}
+
+#[test]
+fn test_parse_signed_messages() -> MyResult<()> {
+    let text = "msg: Listen for me, you better listen for me now. \n\
+                s: 1267396447369736888040262262183731677867615804316\n\
+                r: 1105520928110492191417703162650245113664610474875\n\
+                m: a4db3de27e2db3e5ef085ced2bced91b82e0df19\n";
+    let messages = parse_signed_messages(text)?;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0].msg,
+        "Listen for me, you better listen for me now. "
+    );
+    assert_eq!(
+        messages[0].m,
+        crate::dsa::hash_message(messages[0].msg.as_bytes())
+    );
+    assert_eq!(messages[0].to_string(), text);
+    assert!(parse_signed_messages("msg: hi\ns: 1\nr: 2\n").is_err());
+    assert!(parse_signed_messages("msg: hi\nr: 1\ns: 2\nm: 3\n").is_err());
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::{
+    bigint::BigUint,
+    dsa::{DsaParams, Signature},
+};
+use rand::Rng;
+
+/**
+ * A signature that verifies for every message under `public` when the verifier has
+ * been given a bad generator and skips the range checks (challenge 45), or None if g
+ * isn't one of these:
+ *  - g = 0: g^u1 is 0, so v is 0 and any (0, s) verifies
+ *  - g = 1 mod p, e.g. p + 1: g^u1 is 1, so v is y^u2 = y^(r/s). With r = y^z and
+ *    s = r/z for any z, that's y^z = r. z is drawn from `rng`.
+ */
+pub fn magic_signature(
+    params: &DsaParams,
+    public: &BigUint,
+    rng: &mut impl Rng,
+) -> Option<Signature> {
+    let q = &params.q;
+    if params.g.is_zero() {
+        return Some(Signature {
+            r: BigUint::zero(),
+            s: BigUint::one(),
+        });
+    }
+    if !(&params.g % &params.p).is_one() {
+        return None;
+    }
+    loop {
+        let z = &BigUint::random_below(&(q - &BigUint::one()), rng) + &BigUint::one();
+        let r = &public.modpow(&z, &params.p) % q;
+        if r.is_zero() {
+            continue;
+        }
+        let s = &(&r * &z.modinv(q)?) % q;
+        return Some(Signature { r, s });
+    }
+}
+
+#[test]
+fn test_magic_signature() {
+    use crate::{
+        dsa::{verify_unchecked, Keypair},
+        mt19937::Mt19937,
+    };
+
+    let mut rng = Mt19937::new(45);
+    let params = DsaParams::cryptopals();
+    let keypair = Keypair::generate(&params, &mut rng);
+    assert_eq!(magic_signature(&params, &keypair.public, &mut rng), None);
+
+    let p_plus_1 = &params.p + &BigUint::one();
+    for g in [BigUint::zero(), p_plus_1] {
+        let tampered = DsaParams {
+            g,
+            ..params.clone()
+        };
+        let signature = magic_signature(&tampered, &keypair.public, &mut rng).unwrap();
+        for message in [&b"Hello, world"[..], b"Goodbye, world", b""] {
+            assert!(verify_unchecked(
+                &tampered,
+                &keypair.public,
+                message,
+                &signature
+            ));
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    aes::{detect_ecb11, has_repeating_block, BLOCK_SIZE},
+    MyResult,
+};
+
+/**
+ * Feed the oracle increasingly long inputs until the ciphertext grows; the size of
+ * the jump is the block size.
+ */
+pub fn detect_block_size(oracle: &impl Fn(&[u8]) -> Vec<u8>) -> usize {
+    let base_len = oracle(&[]).len();
+    (1..)
+        .map(|n| oracle(&vec![0; n]).len())
+        .find(|&len| len > base_len)
+        .map(|len| len - base_len)
+        .unwrap()
+}
+
+/**
+ * Three blocks of identical input always contain two aligned identical blocks, no
+ * matter how long any prefix is, which ECB will encrypt identically.
+ */
+pub fn detect_ecb(oracle: &impl Fn(&[u8]) -> Vec<u8>, block_size: usize) -> bool {
+    let ciphertext = oracle(&vec![0; 3 * block_size]);
+    if block_size == BLOCK_SIZE && detect_ecb11(&ciphertext) {
+        return true;
+    }
+    has_repeating_block(&ciphertext, block_size)
+}
+
+fn block(ciphertext: &[u8], idx: usize, block_size: usize) -> &[u8] {
+    &ciphertext[idx * block_size..(idx + 1) * block_size]
+}
+
+/**
+ * Find the length of the oracle's prefix. Two inputs that differ only in their final
+ * byte first differ in the block that holds the end of the prefix. Then pad in front
+ * of the differing byte until that block stops changing: at that point the prefix
+ * plus the padding fills it exactly.
+ */
+pub fn detect_prefix_len(oracle: &impl Fn(&[u8]) -> Vec<u8>, block_size: usize) -> usize {
+    let probe = |pad_len: usize, last: u8| {
+        let mut input = vec![0; pad_len];
+        input.push(last);
+        oracle(&input)
+    };
+
+    let (l, r) = (probe(0, 0), probe(0, 1));
+    let prefix_block = (0..l.len() / block_size)
+        .find(|&idx| block(&l, idx, block_size) != block(&r, idx, block_size))
+        .unwrap();
+
+    let pad_len = (1..=block_size)
+        .find(|&pad_len| {
+            let (l, r) = (probe(pad_len, 0), probe(pad_len, 1));
+            block(&l, prefix_block, block_size) == block(&r, prefix_block, block_size)
+        })
+        .unwrap();
+
+    (prefix_block + 1) * block_size - pad_len
+}
+
+/**
+ * Recovers the secret suffix appended by an ECB oracle, one byte at a time
+ * (challenges 12 and 14). Any prefix is detected and padded out to a block boundary
+ * so the rest of the attack can ignore it.
+ */
+pub fn recover_suffix(oracle: impl Fn(&[u8]) -> Vec<u8>) -> MyResult<Vec<u8>> {
+    let block_size = detect_block_size(&oracle);
+    if !detect_ecb(&oracle, block_size) {
+        return Err("oracle is not using ECB mode".into());
+    }
+
+    let prefix_len = detect_prefix_len(&oracle, block_size);
+    let align_len = (block_size - prefix_len % block_size) % block_size;
+    let skip = prefix_len + align_len;
+    let aligned = |input: &[u8]| {
+        let mut padded = vec![0; align_len];
+        padded.extend_from_slice(input);
+        oracle(&padded)[skip..].to_vec()
+    };
+
+    // The suffix fills the final block exactly once enough input bytes have been
+    // added to push the padding into a block of its own.
+    let base_len = aligned(&[]).len();
+    let fill_len = (1..=block_size)
+        .find(|&n| aligned(&vec![0; n]).len() > base_len)
+        .unwrap();
+    let suffix_len = base_len - fill_len;
+
+    let mut recovered: Vec<u8> = Vec::with_capacity(suffix_len);
+    for i in 0..suffix_len {
+        let block_idx = i / block_size;
+        let short = vec![0; block_size - 1 - (i % block_size)];
+        let target = aligned(&short);
+        let target = block(&target, block_idx, block_size);
+
+        let mut probe = short.clone();
+        probe.extend_from_slice(&recovered);
+        probe.push(0);
+        let byte = (0u8..=255)
+            .find(|&byte| {
+                *probe.last_mut().unwrap() = byte;
+                block(&aligned(&probe), block_idx, block_size) == target
+            })
+            .ok_or_else(|| format!("no byte matched at suffix offset {}", i))?;
+        recovered.push(byte);
+    }
+    Ok(recovered)
+}
+
+#[test]
+fn test_detect_prefix_len() {
+    use crate::aes::{Encrypt, Mode};
+
+    let key = [3; 16];
+    for prefix_len in 0..40 {
+        // prefix and suffix made of the bytes the probes use, to make sure they
+        // can't be confused with the attacker's input
+        let oracle = |input: &[u8]| {
+            let mut plaintext = vec![prefix_len as u8 % 2; prefix_len];
+            plaintext.extend_from_slice(input);
+            plaintext.extend_from_slice(&[0, 1, 0, 1]);
+            plaintext.encrypt(Mode::ECB, &key, None).unwrap()
+        };
+        assert_eq!(detect_block_size(&oracle), 16);
+        assert!(detect_ecb(&oracle, 16));
+        assert_eq!(detect_prefix_len(&oracle, 16), prefix_len);
+    }
+}
+
+#[test]
+fn test_recover_suffix_rejects_cbc() {
+    use crate::aes::{Encrypt, Mode};
+
+    let oracle = |input: &[u8]| input.encrypt(Mode::CBC, &[3; 16], Some(&[0; 16])).unwrap();
+    assert!(recover_suffix(oracle).is_err());
+}
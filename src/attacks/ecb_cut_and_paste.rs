@@ -0,0 +1,112 @@
+use crate::{
+    aes::{pkcs7_pad, random_bytes, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    serializers::kv,
+    MyResult,
+};
+
+/**
+ * Encodes a user profile for `email`. The metacharacters `&` and `=` are stripped
+ * from the email so it can't smuggle in extra pairs.
+ */
+pub fn profile_for(email: &str) -> String {
+    let email: String = email.chars().filter(|&c| c != '&' && c != '=').collect();
+    kv::encode(&[
+        ("email".to_string(), email),
+        ("uid".to_string(), "10".to_string()),
+        ("role".to_string(), "user".to_string()),
+    ])
+}
+
+/**
+ * Hands out ECB-encrypted profiles under a fixed random key and decrypts them again
+ * (challenge 13).
+ */
+pub struct ProfileService {
+    key: Vec<u8>,
+}
+
+impl ProfileService {
+    pub fn new() -> Self {
+        ProfileService {
+            key: random_bytes(BLOCK_SIZE),
+        }
+    }
+
+    pub fn encrypted_profile_for(&self, email: &str) -> Vec<u8> {
+        profile_for(email)
+            .as_bytes()
+            .encrypt(Mode::ECB, &self.key, None)
+            .expect("service key is always valid")
+    }
+
+    pub fn decrypt_profile(&self, ciphertext: &[u8]) -> MyResult<Vec<(String, String)>> {
+        let plaintext = ciphertext.decrypt(Mode::ECB, &self.key, None)?;
+        kv::parse(&String::from_utf8(plaintext)?)
+    }
+
+    pub fn is_admin(&self, ciphertext: &[u8]) -> MyResult<bool> {
+        let profile = self.decrypt_profile(ciphertext)?;
+        Ok(kv::get(&profile, "role") == Some("admin"))
+    }
+}
+
+impl Default for ProfileService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Builds a `role=admin` profile using only the service's encryption of chosen emails.
+ * One email pushes `role=` to the end of a block, another places a padded `admin`
+ * block at a block boundary; the forged ciphertext is the first one's leading blocks
+ * followed by the second one's `admin` block.
+ */
+pub fn forge_admin_profile(encrypted_profile_for: impl Fn(&str) -> Vec<u8>) -> Vec<u8> {
+    let before_email = "email=".len();
+    let before_role = profile_for("").len() - "user".len();
+
+    // "email=" + filler, so that "admin" starts the next block
+    let filler_len = (BLOCK_SIZE - before_email % BLOCK_SIZE) % BLOCK_SIZE;
+    let admin_block_idx = (before_email + filler_len) / BLOCK_SIZE;
+    let mut email = "A".repeat(filler_len);
+    email.push_str(std::str::from_utf8(&pkcs7_pad(b"admin", BLOCK_SIZE)).unwrap());
+    let admin_ciphertext = encrypted_profile_for(&email);
+    let admin_block =
+        &admin_ciphertext[admin_block_idx * BLOCK_SIZE..(admin_block_idx + 1) * BLOCK_SIZE];
+
+    // an email just long enough that the role value starts a block of its own
+    let domain = "@bar.com";
+    let user_len = (1..)
+        .find(|n| (before_role + n + domain.len()).is_multiple_of(BLOCK_SIZE))
+        .unwrap();
+    let email = format!("{}{}", "f".repeat(user_len), domain);
+    let ciphertext = encrypted_profile_for(&email);
+    let role_offset = before_role + email.len();
+
+    let mut forged = ciphertext[..role_offset].to_vec();
+    forged.extend_from_slice(admin_block);
+    forged
+}
+
+#[test]
+fn test_profile_for() {
+    assert_eq!(
+        profile_for("foo@bar.com"),
+        "email=foo@bar.com&uid=10&role=user"
+    );
+    assert_eq!(
+        profile_for("foo@bar.com&role=admin"),
+        "email=foo@bar.comroleadmin&uid=10&role=user"
+    );
+}
+
+#[test]
+fn test_profile_service() -> MyResult<()> {
+    let service = ProfileService::new();
+    let ciphertext = service.encrypted_profile_for("foo@bar.com&role=admin");
+    assert!(!service.is_admin(&ciphertext)?);
+    let profile = service.decrypt_profile(&ciphertext)?;
+    assert_eq!(kv::get(&profile, "email"), Some("foo@bar.comroleadmin"));
+    Ok(())
+}
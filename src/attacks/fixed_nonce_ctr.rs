@@ -0,0 +1,142 @@
+use crate::{
+    letter_frequency::{
+        break_repeating_key_xor_with_keysize, break_single_byte_xor, compute_score,
+        mean_squared_error,
+    },
+    xor::Xor,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ColumnConfidence {
+    // letter frequency score of the column decrypted with the recovered byte, lower is better
+    pub score: u32,
+    // number of ciphertexts long enough to contribute to the column
+    pub samples: usize,
+}
+
+#[derive(Debug)]
+pub struct RecoveredKeystream {
+    pub keystream: Vec<u8>,
+    pub confidence: Vec<ColumnConfidence>,
+}
+
+impl RecoveredKeystream {
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Vec<u8> {
+        let len = ciphertext.len().min(self.keystream.len());
+        ciphertext[..len].xor(&self.keystream[..len])
+    }
+}
+
+/**
+ * The letter frequency score is rounded to an integer, so several keys often tie for
+ * the best score, and which one wins depends on the keystream. Re-rank the tied keys
+ * by the unrounded score.
+ *
+ * The score also ignores case, so when every byte in a column is a letter, `key` and
+ * `key ^ 0x20` tie exactly. Those are ranked by assuming sentence case: capitals at
+ * the start of a line and lowercase everywhere else.
+ */
+fn refine_key(column: &[u8], idx: usize, key: u8) -> u8 {
+    let score = compute_score(&column.xor(&[key]));
+    let rank = |key: u8| {
+        let decoded = column.xor(&[key]);
+        let mse = mean_squared_error(&decoded).unwrap_or(f32::MAX);
+        let wrong_case = decoded
+            .iter()
+            .filter(|b| {
+                if idx == 0 {
+                    b.is_ascii_lowercase()
+                } else {
+                    b.is_ascii_uppercase()
+                }
+            })
+            .count();
+        (mse, wrong_case)
+    };
+    (0u8..=255)
+        .filter(|&candidate| compute_score(&column.xor(&[candidate])) == score)
+        .map(|candidate| (rank(candidate), candidate))
+        .min_by(|(l, _), (r, _)| l.partial_cmp(r).unwrap())
+        .map(|(_, candidate)| candidate)
+        .unwrap_or(key)
+}
+
+fn column(ciphertexts: &[Vec<u8>], idx: usize) -> Vec<u8> {
+    ciphertexts
+        .iter()
+        .filter_map(|ciphertext| ciphertext.get(idx).copied())
+        .collect()
+}
+
+/**
+ * Recovers the keystream shared by ciphertexts that were all encrypted under the same
+ * CTR key and nonce (challenges 19 and 20).
+ *
+ * Truncated to the shortest ciphertext, the concatenated ciphertexts are repeating-key
+ * xor with a key as long as the shortest ciphertext. Past that, each keystream byte is
+ * broken on its own from the ciphertexts that are long enough to reach it, so the
+ * confidence drops as fewer samples remain.
+ *
+ * Where a column's letters could be either case, the tie is broken by guessing that
+ * each line starts with a capital and is lowercase after that. That's a heuristic
+ * that suits the challenge 19 and 20 lines, not something that holds for every
+ * plaintext, so columns of other text may come out with the wrong case.
+ */
+pub fn recover_keystream(ciphertexts: &[Vec<u8>]) -> Option<RecoveredKeystream> {
+    let min_len = ciphertexts.iter().map(Vec::len).min()?;
+    let max_len = ciphertexts.iter().map(Vec::len).max()?;
+    if min_len == 0 {
+        return None;
+    }
+
+    let truncated: Vec<u8> = ciphertexts
+        .iter()
+        .flat_map(|ciphertext| ciphertext[..min_len].to_vec())
+        .collect();
+    let mut keystream = break_repeating_key_xor_with_keysize(&truncated, min_len)?;
+    let mut confidence: Vec<ColumnConfidence> = Vec::with_capacity(max_len);
+    for (idx, key) in keystream.iter_mut().enumerate() {
+        let column = column(ciphertexts, idx);
+        *key = refine_key(&column, idx, *key);
+        confidence.push(ColumnConfidence {
+            score: compute_score(&column.xor(&[*key])),
+            samples: column.len(),
+        });
+    }
+
+    for idx in min_len..max_len {
+        let column = column(ciphertexts, idx);
+        let (key, score) = match break_single_byte_xor(&column) {
+            Some(res) => (refine_key(&column, idx, res.key), res.score),
+            None => (0, u32::MAX),
+        };
+        keystream.push(key);
+        confidence.push(ColumnConfidence {
+            score,
+            samples: column.len(),
+        });
+    }
+
+    Some(RecoveredKeystream {
+        keystream,
+        confidence,
+    })
+}
+
+#[test]
+fn test_recover_keystream_empty() {
+    assert!(recover_keystream(&[]).is_none());
+    assert!(recover_keystream(&[vec![1, 2], vec![]]).is_none());
+}
+
+#[test]
+fn test_refine_key() {
+    // first letters of the challenge 19 lines, which tie with their lowercase versions
+    let first = b"ICFEIOOPAOTABBAAATIHWWSTATWHSSTAHTYHIHTA";
+    let column = first.xor(&[0x42]);
+    assert_eq!(refine_key(&column, 0, 0x42 ^ 0x20), 0x42);
+    assert_eq!(refine_key(&column, 0, 0x42), 0x42);
+    let column = first.to_ascii_lowercase().xor(&[0x42]);
+    assert_eq!(refine_key(&column, 3, 0x42), 0x42);
+    assert_eq!(refine_key(&column, 3, 0x42 ^ 0x20), 0x42);
+}
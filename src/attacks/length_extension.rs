@@ -0,0 +1,96 @@
+use crate::{aes::random_bytes, hash::MerkleDamgard};
+use rand::Rng;
+use std::marker::PhantomData;
+
+pub const MESSAGE: &[u8] =
+    b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+pub const ADMIN: &[u8] = b";admin=true";
+const MAX_KEY_LEN: usize = 64;
+
+/**
+ * Signs messages with a secret-prefix MAC under a random key of unknown length
+ */
+pub struct MacService<H> {
+    key: Vec<u8>,
+    hash: PhantomData<H>,
+}
+
+impl<H: MerkleDamgard> MacService<H> {
+    pub fn new() -> Self {
+        let len = rand::thread_rng().gen_range(1..=32);
+        MacService {
+            key: random_bytes(len),
+            hash: PhantomData,
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> H::Digest {
+        H::secret_prefix_mac(&self.key, message)
+    }
+
+    pub fn verify(&self, message: &[u8], mac: &H::Digest) -> bool {
+        &self.sign(message) == mac
+    }
+
+    pub fn is_admin(&self, message: &[u8], mac: &H::Digest) -> bool {
+        self.verify(message, mac)
+            && message
+                .split(|&b| b == b';')
+                .any(|pair| pair == b"admin=true")
+    }
+}
+
+impl<H: MerkleDamgard> Default for MacService<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Given the MAC of `message` under a key of `key_len` bytes, returns the message with
+ * the original padding and `extension` glued on, and its MAC. The MAC is the hash
+ * registers after the padded key and message, so hashing can pick up from there.
+ */
+pub fn extend<H: MerkleDamgard>(
+    message: &[u8],
+    mac: &H::Digest,
+    extension: &[u8],
+    key_len: usize,
+) -> (Vec<u8>, H::Digest) {
+    let mut forged = message.to_vec();
+    forged.extend_from_slice(&H::md_padding((key_len + message.len()) as u64));
+    let mut hasher = H::from_state(H::state_from_digest(mac), (key_len + forged.len()) as u64);
+    hasher.update(extension);
+    forged.extend_from_slice(extension);
+    (forged, hasher.finalize())
+}
+
+/**
+ * Forges a MAC for `message` extended with `;admin=true` by trying each key length
+ * until the service accepts the forgery (challenges 29 and 30).
+ */
+pub fn forge_admin<H: MerkleDamgard>(
+    message: &[u8],
+    mac: &H::Digest,
+    verify: impl Fn(&[u8], &H::Digest) -> bool,
+) -> Option<(Vec<u8>, H::Digest)> {
+    (0..=MAX_KEY_LEN)
+        .map(|key_len| extend::<H>(message, mac, ADMIN, key_len))
+        .find(|(forged, forged_mac)| verify(forged, forged_mac))
+}
+
+#[test]
+fn test_extend() {
+    use crate::hash::{Md4, Sha1};
+
+    let key = b"YELLOW SUBMARINE";
+    let mac = Sha1::secret_prefix_mac(key, MESSAGE);
+    let (forged, forged_mac) = extend::<Sha1>(MESSAGE, &mac, ADMIN, key.len());
+    assert!(forged.starts_with(MESSAGE));
+    assert!(forged.ends_with(ADMIN));
+    assert_eq!(Sha1::secret_prefix_mac(key, &forged), forged_mac);
+
+    let mac = Md4::secret_prefix_mac(key, MESSAGE);
+    let (forged, forged_mac) = extend::<Md4>(MESSAGE, &mac, ADMIN, key.len());
+    assert_eq!(Md4::secret_prefix_mac(key, &forged), forged_mac);
+}
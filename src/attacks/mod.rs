@@ -0,0 +1,18 @@
+pub mod bitflipping;
+pub mod cbc_key_as_iv;
+pub mod ctr_edit;
+pub mod dh_mitm;
+pub mod dsa_nonce;
+pub mod dsa_params;
+pub mod ecb_byte_at_a_time;
+pub mod ecb_cut_and_paste;
+pub mod fixed_nonce_ctr;
+pub mod length_extension;
+pub mod mt19937_recovery;
+pub mod padding_oracle;
+pub mod rsa_broadcast;
+pub mod rsa_parity;
+pub mod rsa_signature_forgery;
+pub mod rsa_unpadded;
+pub mod srp;
+pub mod timing_leak;
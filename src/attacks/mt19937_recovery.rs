@@ -0,0 +1,87 @@
+use crate::{
+    mt19937::{keystream, untemper, Mt19937, N},
+    xor::Xor,
+};
+use std::ops::RangeInclusive;
+
+/**
+ * Clones a generator from 624 consecutive outputs (challenge 23). The window doesn't
+ * need to line up with a twist: MT19937 is a recurrence over the sequence of state
+ * words, x[k + 624] = f(x[k], x[k + 1], x[k + 397]), so any 624 consecutive words
+ * are enough to continue it.
+ */
+pub fn clone_from_outputs(outputs: &[u32; N]) -> Mt19937 {
+    Mt19937::from_state(outputs.map(untemper))
+}
+
+/**
+ * Clones a generator from a window of at least 624 consecutive outputs, predicting
+ * the outputs that follow the end of the window.
+ */
+pub fn clone_from_window(outputs: &[u32]) -> Option<Mt19937> {
+    let start = outputs.len().checked_sub(N)?;
+    let window: &[u32; N] = outputs[start..].try_into().ok()?;
+    Some(clone_from_outputs(window))
+}
+
+/**
+ * Finds the seed of a generator seeded with a unix timestamp at most `window` seconds
+ * before `now`, given its first output (challenge 22).
+ */
+pub fn crack_timestamp_seed(first_output: u32, now: u32, window: u32) -> Option<u32> {
+    (now.saturating_sub(window)..=now)
+        .rev()
+        .find(|&seed| Mt19937::new(seed).next_u32() == first_output)
+}
+
+#[test]
+fn test_clone_from_window() {
+    let mut mt = Mt19937::new(5489);
+    let outputs: Vec<u32> = (0..2000).map(|_| mt.next_u32()).collect();
+    for start in [0, 1, 100, 623, 624, 1000] {
+        let mut clone = clone_from_window(&outputs[start..start + N]).unwrap();
+        let predicted: Vec<u32> = (0..200).map(|_| clone.next_u32()).collect();
+        assert_eq!(predicted, outputs[start + N..start + N + 200]);
+    }
+    assert!(clone_from_window(&outputs[..N - 1]).is_none());
+}
+
+/**
+ * Recovers the 16-bit seed of an MT19937 stream cipher ciphertext whose plaintext ends
+ * with `known_suffix` (challenge 24). There are only 65536 seeds, so try them all.
+ */
+pub fn recover_stream_key(ciphertext: &[u8], known_suffix: &[u8]) -> Option<u16> {
+    let offset = ciphertext.len().checked_sub(known_suffix.len())?;
+    let suffix_keystream = ciphertext[offset..].xor(known_suffix);
+    (0..=u16::MAX)
+        .find(|&seed| keystream(seed as u32, ciphertext.len())[offset..] == suffix_keystream)
+}
+
+pub const TOKEN_LEN: usize = 16;
+
+/**
+ * A password reset token made from a generator seeded with the current time
+ */
+pub fn password_reset_token(timestamp: u32) -> Vec<u8> {
+    keystream(timestamp, TOKEN_LEN)
+}
+
+/**
+ * Whether `token` was produced by an MT19937 seeded with a timestamp in `time_window`.
+ * An empty token isn't: it would match every seed.
+ */
+pub fn is_mt_token(token: &[u8], time_window: RangeInclusive<u32>) -> bool {
+    !token.is_empty()
+        && time_window
+            .rev()
+            .any(|timestamp| keystream(timestamp, token.len()) == token)
+}
+
+#[test]
+fn test_is_mt_token() {
+    let token = password_reset_token(1_700_000_000);
+    assert!(is_mt_token(&token, 1_699_999_000..=1_700_000_100));
+    assert!(!is_mt_token(&token, 1_700_000_001..=1_700_000_100));
+    assert!(!is_mt_token(&[0; TOKEN_LEN], 1_699_999_000..=1_700_000_100));
+    assert!(!is_mt_token(&[], 1_699_999_000..=1_700_000_100));
+}
@@ -0,0 +1,184 @@
+use crate::{
+    aes::{pkcs7_unpad, random_bytes, AesError, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    serializers::base64,
+    MyResult,
+};
+use rand::Rng;
+use std::path::Path;
+
+/**
+ * Serves one of the challenge 17 strings, CBC-encrypted under a fixed random key with
+ * a fresh random IV, and answers whether a given (iv, ciphertext) pair has valid
+ * padding.
+ */
+pub struct PaddingOracleService {
+    key: Vec<u8>,
+    plaintexts: Vec<Vec<u8>>,
+}
+
+impl PaddingOracleService {
+    pub fn new() -> MyResult<Self> {
+        Ok(PaddingOracleService {
+            key: random_bytes(BLOCK_SIZE),
+            plaintexts: base64::from_file_lines(Path::new("data/challenge17.txt"))?,
+        })
+    }
+
+    pub fn plaintexts(&self) -> &[Vec<u8>] {
+        &self.plaintexts
+    }
+
+    // Returns (iv, ciphertext)
+    pub fn encrypt_random(&self) -> (Vec<u8>, Vec<u8>) {
+        let idx = rand::thread_rng().gen_range(0..self.plaintexts.len());
+        self.encrypt(idx)
+    }
+
+    pub fn encrypt(&self, idx: usize) -> (Vec<u8>, Vec<u8>) {
+        let iv = random_bytes(BLOCK_SIZE);
+        let ciphertext = self.plaintexts[idx]
+            .encrypt(Mode::CBC, &self.key, Some(&iv))
+            .expect("service key is always valid");
+        (iv, ciphertext)
+    }
+
+    /**
+     * False for any ciphertext that doesn't decrypt, including a malformed IV or one
+     * that isn't a whole number of blocks
+     */
+    pub fn has_valid_padding(&self, iv: &[u8], ciphertext: &[u8]) -> bool {
+        ciphertext.decrypt(Mode::CBC, &self.key, Some(iv)).is_ok()
+    }
+}
+
+#[derive(Debug)]
+pub struct PaddingOracleResult {
+    pub plaintext: Vec<u8>,
+    // oracle queries spent on each byte of the padded plaintext
+    pub queries_per_byte: Vec<usize>,
+}
+
+impl PaddingOracleResult {
+    pub fn total_queries(&self) -> usize {
+        self.queries_per_byte.iter().sum()
+    }
+}
+
+/**
+ * Recovers the intermediate state D(block) by forging the preceding block so that the
+ * decrypted padding is 0x01, then 0x02 0x02, etc. Returns the intermediate state and
+ * the number of queries each byte cost, in block order.
+ */
+fn recover_intermediate(
+    block: &[u8],
+    oracle: &mut impl FnMut(&[u8], &[u8]) -> bool,
+) -> MyResult<(Vec<u8>, Vec<usize>)> {
+    let mut intermediate = vec![0u8; BLOCK_SIZE];
+    let mut queries = vec![0usize; BLOCK_SIZE];
+
+    for pad in 1..=BLOCK_SIZE as u8 {
+        let idx = BLOCK_SIZE - pad as usize;
+        let mut forged = vec![0u8; BLOCK_SIZE];
+        for k in (idx + 1)..BLOCK_SIZE {
+            forged[k] = intermediate[k] ^ pad;
+        }
+
+        let mut found = None;
+        for guess in 0u8..=255 {
+            forged[idx] = guess;
+            queries[idx] += 1;
+            if !oracle(&forged, block) {
+                continue;
+            }
+            // With pad 0x01, a hit may really be a longer padding (e.g. 0x02 0x02)
+            // produced by the bytes before it. Changing the byte before the target
+            // only breaks the padding in that case.
+            if pad == 1 {
+                let mut check = forged.clone();
+                check[idx - 1] ^= 0xff;
+                queries[idx] += 1;
+                if !oracle(&check, block) {
+                    continue;
+                }
+            }
+            found = Some(guess);
+            break;
+        }
+
+        match found {
+            Some(guess) => intermediate[idx] = guess ^ pad,
+            None => return Err(format!("no valid padding found for byte {}", idx).into()),
+        }
+    }
+    Ok((intermediate, queries))
+}
+
+/**
+ * CBC padding oracle attack (challenge 17). `oracle(iv, ciphertext)` must answer
+ * whether the ciphertext decrypts with valid PKCS#7 padding. Each block is attacked
+ * on its own by submitting it with a forged IV.
+ */
+pub fn padding_oracle_attack(
+    iv: &[u8],
+    ciphertext: &[u8],
+    mut oracle: impl FnMut(&[u8], &[u8]) -> bool,
+) -> MyResult<PaddingOracleResult> {
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(AesError::InvalidCiphertextLength(ciphertext.len()).into());
+    }
+
+    let mut padded = Vec::with_capacity(ciphertext.len());
+    let mut queries_per_byte = Vec::with_capacity(ciphertext.len());
+    let prevs = std::iter::once(iv).chain(ciphertext.chunks(BLOCK_SIZE));
+    for (prev, block) in prevs.zip(ciphertext.chunks(BLOCK_SIZE)) {
+        let (intermediate, queries) = recover_intermediate(block, &mut oracle)?;
+        padded.extend(intermediate.iter().zip(prev).map(|(i, p)| i ^ p));
+        queries_per_byte.extend(queries);
+    }
+
+    Ok(PaddingOracleResult {
+        plaintext: pkcs7_unpad(&padded, BLOCK_SIZE)?,
+        queries_per_byte,
+    })
+}
+
+#[test]
+fn test_last_byte_ambiguity() -> MyResult<()> {
+    use crate::{aes::Aes, aes::BlockCipher, xor::Xor};
+
+    // Find a block whose intermediate state has 0x02 in its second-to-last byte, so
+    // the all-zero forged IV makes a final byte of 0x02 look like valid padding too.
+    let aes = Aes::new(&[7; 16])?;
+    let (block, intermediate) = (0u64..)
+        .map(|n| {
+            let block = [n.to_le_bytes(), [0; 8]].concat();
+            let intermediate = aes.decrypt_block(&block);
+            (block, intermediate)
+        })
+        .find(|(_, intermediate)| intermediate[14] == 0x02)
+        .unwrap();
+    let plaintext = b"YELLOW SUBMARIN\x01";
+    let iv = intermediate.xor(plaintext);
+
+    let result = padding_oracle_attack(&iv, &block, |iv, ciphertext| {
+        pkcs7_unpad(&aes.decrypt_block(ciphertext).xor(iv), BLOCK_SIZE).is_ok()
+    })?;
+    assert_eq!(result.plaintext, b"YELLOW SUBMARIN");
+    assert_eq!(result.queries_per_byte.len(), 16);
+    assert!(result
+        .queries_per_byte
+        .iter()
+        .all(|&q| (1..=257).contains(&q)));
+    Ok(())
+}
+
+#[test]
+fn test_has_valid_padding() -> MyResult<()> {
+    let service = PaddingOracleService::new()?;
+    let (iv, ciphertext) = service.encrypt(0);
+    assert!(service.has_valid_padding(&iv, &ciphertext));
+    assert!(!service.has_valid_padding(&iv, &ciphertext[1..]));
+    assert!(!service.has_valid_padding(&iv, &[]));
+    assert!(!service.has_valid_padding(&iv[1..], &ciphertext));
+    Ok(())
+}
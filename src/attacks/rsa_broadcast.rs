@@ -0,0 +1,32 @@
+use crate::{
+    bigint::BigUint,
+    rsa::{invmod, PublicKey},
+    MyResult,
+};
+
+/**
+ * Recovers a message that was encrypted to three different e=3 public keys
+ * (challenge 40). By the CRT, the three ciphertexts give m^3 mod n0*n1*n2, and since
+ * m is less than each modulus, m^3 is less than their product and the cube root is
+ * exact.
+ */
+pub fn broadcast_attack(ciphertexts: &[(BigUint, PublicKey); 3]) -> MyResult<Vec<u8>> {
+    let three = BigUint::from(3u32);
+    if ciphertexts.iter().any(|(_, key)| key.e != three) {
+        return Err("broadcast attack needs e = 3".into());
+    }
+    let product = ciphertexts
+        .iter()
+        .fold(BigUint::one(), |product, (_, key)| &product * &key.n);
+    let mut result = BigUint::zero();
+    for (c, key) in ciphertexts {
+        let others = &product / &key.n;
+        let inverse = invmod(&others, &key.n).ok_or("moduli are not pairwise coprime")?;
+        result = &(&result + &(&(c * &others) * &inverse)) % &product;
+    }
+    let m = result.cbrt();
+    if m.pow(3) != result {
+        return Err("combined ciphertext is not a perfect cube".into());
+    }
+    Ok(m.to_bytes_be())
+}
@@ -0,0 +1,93 @@
+use crate::{
+    bigint::BigUint,
+    rsa::{Keypair, PublicKey},
+};
+
+/**
+ * Decrypts anything, but only says whether the plaintext is even (challenge 46)
+ */
+pub struct ParityOracle {
+    keypair: Keypair,
+}
+
+impl ParityOracle {
+    pub fn new(keypair: Keypair) -> Self {
+        ParityOracle { keypair }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.keypair.public
+    }
+
+    pub fn is_even(&self, ciphertext: &[u8]) -> bool {
+        self.keypair
+            .private
+            .decrypt_int(&BigUint::from_bytes_be(ciphertext))
+            .is_even()
+    }
+}
+
+/**
+ * Called with the current upper bound on the plaintext after each query
+ */
+pub type Progress<'a> = &'a mut dyn FnMut(&[u8]);
+
+/**
+ * Recovers the plaintext with one parity query per bit of N.
+ *
+ * Multiplying the ciphertext by 2^e doubles the plaintext. Since N is odd, 2m mod N is
+ * even exactly when 2m didn't wrap, i.e. when m < N/2, and each further doubling halves
+ * the interval the same way. After i queries the plaintext lies strictly between
+ * lo * N / 2^i and hi * N / 2^i, and only the numerators are tracked, so the bounds
+ * are exact. Once the interval is narrower than 1, the plaintext is the largest
+ * integer below the upper bound.
+ *
+ * `progress` gets the upper bound after every query, which converges on the plaintext.
+ */
+pub fn recover_plaintext(
+    public: &PublicKey,
+    ciphertext: &[u8],
+    is_even: impl Fn(&[u8]) -> bool,
+    mut progress: Option<Progress>,
+) -> Vec<u8> {
+    let n = &public.n;
+    let double = public.encrypt_int(&BigUint::from(2u32));
+    let mut c = BigUint::from_bytes_be(ciphertext);
+    let (mut lo, mut hi) = (BigUint::zero(), BigUint::one());
+    let one = BigUint::one();
+    let bits = n.bits();
+    for i in 1..=bits {
+        c = &(&c * &double) % n;
+        let mid = &lo + &hi;
+        if is_even(&c.to_bytes_be()) {
+            lo = &lo << 1;
+            hi = mid;
+        } else {
+            lo = mid;
+            hi = &hi << 1;
+        }
+        if let Some(progress) = progress.as_mut() {
+            progress(&(&(&(&hi * n) - &one) >> i).to_bytes_be());
+        }
+    }
+    (&(&(&hi * n) - &one) >> bits).to_bytes_be()
+}
+
+#[test]
+fn test_recover_plaintext() {
+    use crate::mt19937::Mt19937;
+
+    let oracle = ParityOracle::new(Keypair::generate(256, 65537, &mut Mt19937::new(46)));
+    let public = oracle.public_key();
+    let max = (&public.n - &BigUint::one()).to_bytes_be();
+    for plaintext in [&b""[..], &[1], &[0xff; 31], b"YELLOW SUBMARINE", &max] {
+        let ciphertext = public.encrypt_int(&BigUint::from_bytes_be(plaintext));
+        let recovered = recover_plaintext(
+            public,
+            &ciphertext.to_bytes_be(),
+            |c| oracle.is_even(c),
+            None,
+        );
+        assert_eq!(recovered, plaintext);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    bigint::BigUint,
+    hash::{sha1, sha1::DIGEST_SIZE},
+    rsa::{PublicKey, SHA1_DIGEST_INFO},
+    MyResult,
+};
+
+/**
+ * Checks a PKCS#1 v1.5 SHA-1 signature the way a careless parser would: it walks past
+ * 00 01, the 0xff padding and the 00, then checks the DigestInfo and hash, and never
+ * looks at whatever follows the hash.
+ */
+pub fn verify_sloppy(public: &PublicKey, message: &[u8], signature: &[u8]) -> bool {
+    let s = BigUint::from_bytes_be(signature);
+    if s >= public.n {
+        return false;
+    }
+    let encoded = match public.encrypt_int(&s).to_bytes_be_padded(public.size()) {
+        Some(encoded) => encoded,
+        None => return false,
+    };
+    let rest = match encoded.strip_prefix(&[0x00, 0x01]) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let padding = rest.iter().take_while(|&&b| b == 0xff).count();
+    if padding == 0 {
+        return false;
+    }
+    let rest = match rest[padding..].strip_prefix(&[0x00]) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    match rest.strip_prefix(&SHA1_DIGEST_INFO) {
+        Some(digest) => digest.get(..DIGEST_SIZE) == Some(&sha1(message)[..]),
+        None => false,
+    }
+}
+
+/**
+ * Forges a signature for any message under an e=3 key that `verify_sloppy` accepts
+ * (challenge 42). The block 00 01 ff 00 DigestInfo || hash is followed by as much
+ * garbage as the modulus allows, and with enough garbage some cube lands inside the
+ * range of blocks with that prefix. Taking the cube root of the largest such block
+ * finds it.
+ */
+pub fn forge_signature(public: &PublicKey, message: &[u8]) -> MyResult<Vec<u8>> {
+    if public.e != BigUint::from(3u32) {
+        return Err("forgery needs e = 3".into());
+    }
+    let mut prefix = vec![0x00, 0x01, 0xff, 0x00];
+    prefix.extend_from_slice(&SHA1_DIGEST_INFO);
+    prefix.extend_from_slice(&sha1(message));
+    let len = public.size();
+    if len < prefix.len() {
+        return Err("modulus is too short".into());
+    }
+    let mut block = prefix.clone();
+    block.resize(len, 0xff);
+    let signature = BigUint::from_bytes_be(&block).cbrt();
+    let forged = signature
+        .pow(3)
+        .to_bytes_be_padded(len)
+        .ok_or("forged block is too long")?;
+    if !forged.starts_with(&prefix) {
+        return Err("not enough room after the hash for a cube to fit".into());
+    }
+    Ok(signature.to_bytes_be_padded(len).expect("signature fits"))
+}
+
+#[test]
+fn test_verify_sloppy() -> MyResult<()> {
+    use crate::{mt19937::Mt19937, rsa::Keypair};
+
+    let keypair = Keypair::generate(512, 3, &mut Mt19937::new(42));
+    let signature = keypair.private.sign(b"hi mom")?;
+    assert!(verify_sloppy(&keypair.public, b"hi mom", &signature));
+    assert!(!verify_sloppy(&keypair.public, b"hi dad", &signature));
+    assert!(!verify_sloppy(&keypair.public, b"hi mom", &[1]));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::{
+    bigint::BigUint,
+    rsa::{invmod, Keypair, PublicKey},
+    MyResult,
+};
+use rand::Rng;
+use std::collections::HashSet;
+
+/**
+ * Decrypts any ciphertext it's given, but only once (challenge 41). Ciphertexts are
+ * remembered as numbers, so leading zero bytes don't make a repeat look new.
+ */
+pub struct UnpaddedRsaService {
+    keypair: Keypair,
+    seen: HashSet<BigUint>,
+}
+
+impl UnpaddedRsaService {
+    pub fn new(keypair: Keypair) -> Self {
+        UnpaddedRsaService {
+            keypair,
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.keypair.public
+    }
+
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> MyResult<Vec<u8>> {
+        let c = BigUint::from_bytes_be(ciphertext);
+        if self.seen.contains(&c) {
+            return Err("ciphertext has already been decrypted".into());
+        }
+        // only a successful decryption counts, so a rejected ciphertext can be resent
+        let plaintext = self.keypair.private.decrypt(ciphertext)?;
+        self.seen.insert(c);
+        Ok(plaintext)
+    }
+}
+
+/**
+ * Decrypts a ciphertext the service won't decrypt again by blinding it: the service
+ * happily decrypts C' = s^e * C mod N to P' = s * P mod N, and dividing by s gives P.
+ */
+pub fn recover_plaintext(
+    public: &PublicKey,
+    ciphertext: &[u8],
+    rng: &mut impl Rng,
+    mut decrypt: impl FnMut(&[u8]) -> MyResult<Vec<u8>>,
+) -> MyResult<Vec<u8>> {
+    let c = BigUint::from_bytes_be(ciphertext);
+    let two = BigUint::from(2u32);
+    // s must be invertible mod N and shouldn't leave C unchanged
+    let (s, s_inverse) = loop {
+        let s = &BigUint::random_below(&(&public.n - &two), rng) + &two;
+        if let Some(s_inverse) = invmod(&s, &public.n) {
+            break (s, s_inverse);
+        }
+    };
+    let blinded = &(&public.encrypt_int(&s) * &c) % &public.n;
+    let p = BigUint::from_bytes_be(&decrypt(&blinded.to_bytes_be())?);
+    Ok((&(&p * &s_inverse) % &public.n).to_bytes_be())
+}
+
+#[test]
+fn test_refuses_repeats() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    let mut service = UnpaddedRsaService::new(Keypair::generate(256, 3, &mut Mt19937::new(41)));
+    let ciphertext = service.public_key().encrypt(b"secret")?;
+    assert_eq!(service.decrypt(&ciphertext)?, b"secret");
+    assert!(service.decrypt(&ciphertext).is_err());
+
+    let mut padded = vec![0, 0];
+    padded.extend_from_slice(&ciphertext);
+    assert!(service.decrypt(&padded).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_retries_rejected() {
+    use crate::mt19937::Mt19937;
+
+    let mut service = UnpaddedRsaService::new(Keypair::generate(256, 3, &mut Mt19937::new(41)));
+    let too_large = service.public_key().n.to_bytes_be();
+    for _ in 0..2 {
+        let err = service.decrypt(&too_large).unwrap_err();
+        assert_eq!(err.to_string(), "ciphertext is too large for the modulus");
+    }
+    assert!(service.seen.is_empty());
+}
@@ -0,0 +1,129 @@
+use crate::{
+    aes::random_bytes,
+    bigint::BigUint,
+    dh::DhParams,
+    srp::{private_key, proof, session_key, Server, SimpleChallenge, SimpleSrpServer},
+    MyResult,
+};
+
+/**
+ * Logs in as `email` without the password by sending an A that's a multiple of N
+ * (challenge 37). The server's secret (Av^u)^b is then 0 whatever the password is.
+ */
+pub fn login_with_zero_key(server: &mut Server, email: &str, a_public: &BigUint) -> MyResult<bool> {
+    let (salt, _) = server.start_login(email, a_public)?;
+    let client_proof = proof(&session_key(&BigUint::zero()), &salt);
+    Ok(server.verify(email, &client_proof))
+}
+
+pub const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "password",
+    "12345678",
+    "qwerty",
+    "123456789",
+    "12345",
+    "1234",
+    "111111",
+    "1234567",
+    "dragon",
+    "123123",
+    "baseball",
+    "abc123",
+    "football",
+    "monkey",
+    "letmein",
+    "shadow",
+    "master",
+    "696969",
+    "michael",
+    "mustang",
+    "666666",
+    "qwertyuiop",
+    "123321",
+    "1234567890",
+    "superman",
+    "654321",
+    "1qaz2wsx",
+    "7777777",
+    "qazwsx",
+    "jordan",
+    "jennifer",
+    "123qwe",
+    "121212",
+    "killer",
+    "trustno1",
+    "hunter",
+    "harley",
+    "zxcvbnm",
+    "asdfgh",
+    "buster",
+    "andrew",
+    "batman",
+    "soccer",
+    "tigger",
+    "charlie",
+    "robert",
+    "sunshine",
+    "iloveyou",
+    "hunter2",
+    "starwars",
+    "whatever",
+    "princess",
+];
+
+/**
+ * Poses as the simplified-SRP server (challenge 38). Choosing b = 1 and u = 1 makes
+ * the client's secret B^(a + ux) = A * g^x, so each password guess can be checked
+ * offline against the captured proof.
+ */
+pub struct SimpleSrpMitm {
+    params: DhParams,
+    salt: Vec<u8>,
+    a_public: Option<BigUint>,
+    captured_proof: Option<Vec<u8>>,
+}
+
+impl SimpleSrpMitm {
+    pub fn new() -> Self {
+        SimpleSrpMitm {
+            params: DhParams::nist(),
+            salt: random_bytes(16),
+            a_public: None,
+            captured_proof: None,
+        }
+    }
+
+    pub fn crack<'a>(&self, dictionary: &[&'a str]) -> Option<&'a str> {
+        let DhParams { p, g } = &self.params;
+        let a_public = self.a_public.as_ref()?;
+        let captured_proof = self.captured_proof.as_ref()?;
+        dictionary.iter().copied().find(|password| {
+            let x = private_key(&self.salt, password);
+            let secret = &(a_public * &g.modpow(&x, p)) % p;
+            &proof(&session_key(&secret), &self.salt) == captured_proof
+        })
+    }
+}
+
+impl Default for SimpleSrpMitm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimpleSrpServer for SimpleSrpMitm {
+    fn start_login(&mut self, _email: &str, a_public: &BigUint) -> MyResult<SimpleChallenge> {
+        self.a_public = Some(a_public.clone());
+        Ok(SimpleChallenge {
+            salt: self.salt.clone(),
+            b_public: self.params.g.clone(),
+            u: BigUint::one(),
+        })
+    }
+
+    fn verify(&mut self, _email: &str, client_proof: &[u8]) -> bool {
+        self.captured_proof = Some(client_proof.to_vec());
+        false
+    }
+}
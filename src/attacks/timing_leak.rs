@@ -0,0 +1,254 @@
+use crate::{
+    aes::random_bytes,
+    hash::hmac_sha1,
+    serializers::{from_hex, Serialize},
+    MyResult,
+};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/**
+ * Compares byte by byte, sleeping after each matching byte and bailing out at the
+ * first mismatch, so the time taken leaks the length of the matching prefix
+ */
+pub fn insecure_compare(l: &[u8], r: &[u8], delay: Duration) -> bool {
+    if l.len() != r.len() {
+        return false;
+    }
+    for (l, r) in l.iter().zip(r) {
+        if l != r {
+            return false;
+        }
+        thread::sleep(delay);
+    }
+    true
+}
+
+/**
+ * A tiny HTTP server on loopback that answers `GET /test?file=...&signature=...` with
+ * 200 when the signature is the HMAC-SHA1 of the file name, and 500 otherwise
+ * (challenges 31 and 32). The MAC may be truncated to `mac_len` bytes to keep the
+ * attack short. The server stops when dropped.
+ */
+pub struct TimingServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TimingServer {
+    pub fn start(delay: Duration, mac_len: usize) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let key = random_bytes(16);
+        let handle = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // a misbehaving client only loses its own connection
+                        let _ = handle_connection(stream, &key, delay, mac_len);
+                    }
+                }
+            })
+        };
+        Ok(TimingServer {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for TimingServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // wake up the blocking accept so the thread sees the flag
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    key: &[u8],
+    delay: Duration,
+    mac_len: usize,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // read the headers too, so closing the connection doesn't reset it mid-request
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+    let valid = match parse_query(&request_line) {
+        Some((file, signature)) => {
+            let mac = hmac_sha1(key, file.as_bytes());
+            insecure_compare(&signature, &mac[..mac_len.min(mac.len())], delay)
+        }
+        None => false,
+    };
+    let status = if valid {
+        "200 OK"
+    } else {
+        "500 Internal Server Error"
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    );
+    stream.write_all(response.as_bytes())
+}
+
+// the file and decoded signature from "GET /test?file=foo&signature=abcd HTTP/1.1"
+fn parse_query(request_line: &str) -> Option<(String, Vec<u8>)> {
+    let target = request_line.strip_prefix("GET ")?.split(' ').next()?;
+    let query = target.strip_prefix("/test?")?;
+    let (mut file, mut signature) = (None, None);
+    for pair in query.split('&') {
+        match pair.split_once('=')? {
+            ("file", value) => file = Some(value.to_string()),
+            ("signature", value) => signature = from_hex(value).ok(),
+            _ => {}
+        }
+    }
+    Some((file?, signature?))
+}
+
+/**
+ * Asks the server whether `signature` is valid for `file`
+ */
+pub fn check_signature(addr: SocketAddr, file: &str, signature: &[u8]) -> io::Result<bool> {
+    let mut stream = TcpStream::connect(addr)?;
+    let request = format!(
+        "GET /test?file={}&signature={} HTTP/1.1\r\nHost: {}\r\n\r\n",
+        file,
+        signature.to_hex(),
+        addr
+    );
+    stream.write_all(request.as_bytes())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response.starts_with("HTTP/1.1 200"))
+}
+
+fn median(times: &[Duration]) -> Duration {
+    let mut sorted = times.to_vec();
+    sorted.sort();
+    sorted[sorted.len() / 2]
+}
+
+/**
+ * Recovers a `mac_len`-byte MAC one byte at a time from how long `query` takes to
+ * reject it (challenges 31 and 32).
+ */
+pub fn recover_mac(
+    mac_len: usize,
+    samples: usize,
+    mut query: impl FnMut(&[u8]) -> io::Result<bool>,
+) -> MyResult<Vec<u8>> {
+    recover_mac_timed(mac_len, samples, |mac| {
+        let start = Instant::now();
+        let valid = query(mac)?;
+        Ok((valid, start.elapsed()))
+    })
+}
+
+/**
+ * The attack behind `recover_mac`, with `query` reporting both whether the MAC was
+ * accepted and how long that took.
+ *
+ * For each byte, every candidate is timed `samples` times, then the slower half is
+ * kept and timed again, until one candidate is left. Each round adds samples to the
+ * candidates that are still in the running, so a small leak is judged on the median
+ * of many samples rather than on one noisy measurement.
+ */
+pub fn recover_mac_timed(
+    mac_len: usize,
+    samples: usize,
+    mut query: impl FnMut(&[u8]) -> io::Result<(bool, Duration)>,
+) -> MyResult<Vec<u8>> {
+    let mut mac = vec![0; mac_len];
+    for idx in 0..mac_len {
+        let mut candidates: Vec<(u8, Vec<Duration>)> =
+            (0..=255).map(|byte| (byte, Vec::new())).collect();
+        while candidates.len() > 1 {
+            for (byte, times) in candidates.iter_mut() {
+                mac[idx] = *byte;
+                for _ in 0..samples {
+                    let (valid, elapsed) = query(&mac)?;
+                    if valid {
+                        return Ok(mac);
+                    }
+                    times.push(elapsed);
+                }
+            }
+            candidates.sort_by_cached_key(|(_, times)| std::cmp::Reverse(median(times)));
+            candidates.truncate(candidates.len() / 2);
+        }
+        mac[idx] = candidates[0].0;
+    }
+    Err("no candidate mac was accepted".into())
+}
+
+#[test]
+fn test_insecure_compare() {
+    let delay = Duration::from_millis(2);
+    assert!(insecure_compare(b"abc", b"abc", delay));
+    assert!(!insecure_compare(b"abc", b"abd", delay));
+    assert!(!insecure_compare(b"abc", b"ab", delay));
+}
+
+#[test]
+fn test_parse_query() {
+    assert_eq!(
+        parse_query("GET /test?file=foo&signature=0aff HTTP/1.1\r\n"),
+        Some(("foo".to_string(), vec![0x0a, 0xff]))
+    );
+    assert_eq!(parse_query("GET /test?file=foo HTTP/1.1\r\n"), None);
+    assert_eq!(
+        parse_query("POST /test?file=foo&signature=00 HTTP/1.1\r\n"),
+        None
+    );
+}
+
+#[test]
+fn test_recover_mac_timed() -> MyResult<()> {
+    use crate::{hash::sha1::DIGEST_SIZE, mt19937::Mt19937};
+    use rand::Rng;
+
+    // a simulated server: 1ms per matching byte, plus up to 1.5ms of seeded noise
+    let mac = hmac_sha1(b"key", b"foo");
+    let mut noise = Mt19937::new(31);
+    let recovered = recover_mac_timed(DIGEST_SIZE, 3, |signature| {
+        let matching = signature
+            .iter()
+            .zip(&mac)
+            .take_while(|(l, r)| l == r)
+            .count();
+        let elapsed = Duration::from_micros(matching as u64 * 1000 + noise.gen_range(0..1500));
+        Ok((signature == mac, elapsed))
+    })?;
+    assert_eq!(recovered, mac);
+    Ok(())
+}
@@ -0,0 +1,661 @@
+use crate::{
+    serializers::{from_hex, Serialize},
+    MyResult,
+};
+use rand::Rng;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Rem, Shl, Shr, Sub},
+};
+
+/**
+ * An arbitrary-precision unsigned integer, stored as little-endian 32-bit limbs with
+ * no trailing zero limbs, so zero is the empty vec.
+ */
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+const LIMB_BITS: usize = 32;
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint::from(1u32)
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|limb| limb & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    /**
+     * The number of bits needed to represent the number, 0 for zero
+     */
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * LIMB_BITS - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn bit(&self, idx: usize) -> bool {
+        self.limbs
+            .get(idx / LIMB_BITS)
+            .is_some_and(|limb| limb >> (idx % LIMB_BITS) & 1 == 1)
+    }
+
+    /**
+     * Big-endian bytes without leading zeros, empty for zero
+     */
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect();
+        let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+        bytes[leading_zeros..].to_vec()
+    }
+
+    /**
+     * Big-endian bytes left-padded with zeros to `len`, or None if it doesn't fit
+     */
+    pub fn to_bytes_be_padded(&self, len: usize) -> Option<Vec<u8>> {
+        let bytes = self.to_bytes_be();
+        let padding = len.checked_sub(bytes.len())?;
+        let mut padded = vec![0; padding];
+        padded.extend_from_slice(&bytes);
+        Some(padded)
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u32, |limb, &byte| (limb << 8) | byte as u32)
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    /**
+     * Parses hex digits, allowing an odd number of them and surrounding whitespace
+     */
+    pub fn from_hex(s: &str) -> MyResult<Self> {
+        let s: String = s.split_whitespace().collect();
+        let s = if !s.len().is_multiple_of(2) {
+            format!("0{}", s)
+        } else {
+            s
+        };
+        Ok(BigUint::from_bytes_be(&from_hex(&s)?))
+    }
+
+    /**
+     * Lowercase hex of the big-endian bytes, so always a whole number of bytes, and
+     * empty for zero, like `Serialize::to_hex` of `to_bytes_be`
+     */
+    pub fn to_hex(&self) -> String {
+        self.to_bytes_be().to_hex()
+    }
+
+    pub fn from_dec(s: &str) -> MyResult<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("empty decimal string".into());
+        }
+        let ten = BigUint::from(10u32);
+        s.chars().try_fold(BigUint::zero(), |acc, c| {
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| format!("Could not convert decimal char {} to digit", c))?;
+            Ok(&(&acc * &ten) + &BigUint::from(digit))
+        })
+    }
+
+    pub fn to_dec(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // peel off 9 decimal digits at a time
+        let chunk = BigUint::from(1_000_000_000u32);
+        let mut chunks = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = n.div_rem(&chunk);
+            chunks.push(r.limbs.first().copied().unwrap_or(0));
+            n = q;
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:09}", chunk));
+        }
+        s
+    }
+
+    fn add_ref(&self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0u64;
+        for (idx, &limb) in long.iter().enumerate() {
+            let sum = limb as u64 + *short.get(idx).unwrap_or(&0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> LIMB_BITS;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+
+    /**
+     * self - other, or None if that would be negative
+     */
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (idx, &limb) in self.limbs.iter().enumerate() {
+            let mut diff = limb as i64 - *other.limbs.get(idx).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << LIMB_BITS;
+                borrow = 1;
+            }
+            limbs.push(diff as u32);
+        }
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    fn mul_ref(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &l) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &r) in other.limbs.iter().enumerate() {
+                let t = l as u64 * r as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = t as u32;
+                carry = t >> LIMB_BITS;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+
+    fn div_rem_limb(&self, divisor: u32) -> (BigUint, u32) {
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut rem = 0u64;
+        for (idx, &limb) in self.limbs.iter().enumerate().rev() {
+            let cur = (rem << LIMB_BITS) | limb as u64;
+            quotient[idx] = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        (BigUint::from_limbs(quotient), rem as u32)
+    }
+
+    /**
+     * Quotient and remainder, by Knuth's Algorithm D (TAOCP vol. 2, 4.3.1), following
+     * the divmnu version in Hacker's Delight. Panics on division by zero.
+     */
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "division by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (q, r) = self.div_rem_limb(divisor.limbs[0]);
+            return (q, BigUint::from(r));
+        }
+
+        // normalize so the divisor's top limb has its high bit set, which keeps the
+        // estimated quotient digit within 2 of the real one
+        let shift = divisor.limbs.last().unwrap().leading_zeros() as usize;
+        let v = (divisor << shift).limbs;
+        let mut u = (self << shift).limbs;
+        if u.len() == self.limbs.len() {
+            u.push(0);
+        }
+        let n = v.len();
+        let m = u.len() - n - 1;
+        let base = 1u64 << LIMB_BITS;
+        let mut q = vec![0u32; m + 1];
+
+        for j in (0..=m).rev() {
+            let num = ((u[j + n] as u64) << LIMB_BITS) | u[j + n - 1] as u64;
+            let mut qhat = num / v[n - 1] as u64;
+            let mut rhat = num % v[n - 1] as u64;
+            while qhat >= base
+                || qhat * v[n - 2] as u64 > ((rhat << LIMB_BITS) | u[j + n - 2] as u64)
+            {
+                qhat -= 1;
+                rhat += v[n - 1] as u64;
+                if rhat >= base {
+                    break;
+                }
+            }
+
+            // multiply and subtract
+            let mut borrow = 0i64;
+            for i in 0..n {
+                let p = qhat * v[i] as u64;
+                let t = u[i + j] as i64 - borrow - (p & 0xffffffff) as i64;
+                u[i + j] = t as u32;
+                borrow = (p >> LIMB_BITS) as i64 - (t >> LIMB_BITS);
+            }
+            let t = u[j + n] as i64 - borrow;
+            u[j + n] = t as u32;
+
+            q[j] = qhat as u32;
+            if t < 0 {
+                // qhat was one too large, add the divisor back
+                q[j] = q[j].wrapping_sub(1);
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let t = u[i + j] as u64 + v[i] as u64 + carry;
+                    u[i + j] = t as u32;
+                    carry = t >> LIMB_BITS;
+                }
+                u[j + n] = u[j + n].wrapping_add(carry as u32);
+            }
+        }
+
+        u.truncate(n);
+        (BigUint::from_limbs(q), &BigUint::from_limbs(u) >> shift)
+    }
+
+    /**
+     * self^exponent mod modulus, by left-to-right square and multiply
+     */
+    pub fn modpow(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        assert!(!modulus.is_zero(), "modulus must not be zero");
+        let base = self % modulus;
+        let mut result = &BigUint::one() % modulus;
+        for idx in (0..exponent.bits()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.bit(idx) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+        result
+    }
+
+    /**
+     * The inverse of self mod `modulus` by the extended Euclidean algorithm, or None if
+     * they aren't coprime. The Bezout coefficient is kept reduced mod `modulus` so it
+     * never goes negative.
+     */
+    pub fn modinv(&self, modulus: &BigUint) -> Option<BigUint> {
+        if modulus.is_zero() {
+            return None;
+        }
+        let (mut old_r, mut r) = (self % modulus, modulus.clone());
+        let (mut old_s, mut s) = (BigUint::one(), BigUint::zero());
+        while !r.is_zero() {
+            let (q, rem) = old_r.div_rem(&r);
+            old_r = std::mem::replace(&mut r, rem);
+            let qs = &(&q * &s) % modulus;
+            let next_s = &(&old_s + modulus) - &qs;
+            old_s = std::mem::replace(&mut s, &next_s % modulus);
+        }
+        if old_r.is_one() {
+            Some(&old_s % modulus)
+        } else {
+            None
+        }
+    }
+
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = std::mem::replace(&mut b, r);
+        }
+        a
+    }
+
+    pub fn pow(&self, exponent: u32) -> BigUint {
+        let mut result = BigUint::one();
+        for _ in 0..exponent {
+            result = &result * self;
+        }
+        result
+    }
+
+    /**
+     * The floor of the nth root, by Newton's method starting from a power of two that
+     * is known to be too large
+     */
+    pub fn nth_root(&self, n: u32) -> BigUint {
+        assert!(n > 0, "0th root is undefined");
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+        let n_big = BigUint::from(n);
+        let n_minus_1 = BigUint::from(n - 1);
+        let mut x = &BigUint::one() << self.bits().div_ceil(n as usize);
+        loop {
+            let y = &(&(&n_minus_1 * &x) + &(self / &x.pow(n - 1))) / &n_big;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn cbrt(&self) -> BigUint {
+        self.nth_root(3)
+    }
+
+    /**
+     * A uniformly random number in 0..bound, by rejection sampling numbers with as many
+     * bits as the bound
+     */
+    pub fn random_below(bound: &BigUint, rng: &mut impl Rng) -> BigUint {
+        assert!(!bound.is_zero(), "bound must not be zero");
+        let bits = bound.bits();
+        let mut bytes = vec![0u8; bits.div_ceil(8)];
+        loop {
+            rng.fill(&mut bytes[..]);
+            bytes[0] &= 0xff >> (bytes.len() * 8 - bits);
+            let candidate = BigUint::from_bytes_be(&bytes);
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(n: u32) -> Self {
+        BigUint::from_limbs(vec![n])
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        BigUint::from_limbs(vec![n as u32, (n >> LIMB_BITS) as u32])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_dec())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            write!(f, "BigUint(0x00)")
+        } else {
+            write!(f, "BigUint(0x{})", self.to_hex())
+        }
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let (limb_shift, bit_shift) = (shift / LIMB_BITS, shift % LIMB_BITS);
+        let mut limbs = vec![0u32; limb_shift];
+        if bit_shift == 0 {
+            limbs.extend_from_slice(&self.limbs);
+        } else {
+            let mut carry = 0u32;
+            for &limb in &self.limbs {
+                limbs.push((limb << bit_shift) | carry);
+                carry = limb >> (LIMB_BITS - bit_shift);
+            }
+            limbs.push(carry);
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (limb_shift, bit_shift) = (shift / LIMB_BITS, shift % LIMB_BITS);
+        if limb_shift >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let limbs = &self.limbs[limb_shift..];
+        if bit_shift == 0 {
+            return BigUint::from_limbs(limbs.to_vec());
+        }
+        let shifted = limbs
+            .iter()
+            .enumerate()
+            .map(|(idx, &limb)| {
+                let high = limbs
+                    .get(idx + 1)
+                    .map_or(0, |next| next << (LIMB_BITS - bit_shift));
+                (limb >> bit_shift) | high
+            })
+            .collect();
+        BigUint::from_limbs(shifted)
+    }
+}
+
+// implements an operator for every combination of owned and borrowed operands in terms
+// of the borrowed one
+macro_rules! forward_binop {
+    ($trait:ident, $method:ident, |$l:ident, $r:ident| $body:expr) => {
+        impl $trait<&BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                let ($l, $r) = (self, other);
+                $body
+            }
+        }
+
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, |l, r| l.add_ref(r));
+forward_binop!(Sub, sub, |l, r| l
+    .checked_sub(r)
+    .expect("BigUint subtraction underflowed"));
+forward_binop!(Mul, mul, |l, r| l.mul_ref(r));
+forward_binop!(Div, div, |l, r| l.div_rem(r).0);
+forward_binop!(Rem, rem, |l, r| l.div_rem(r).1);
+
+#[test]
+fn test_matches_u128() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..1000 {
+        let (l, r): (u64, u64) = (rng.gen(), rng.gen());
+        let r = r >> rng.gen_range(0..64);
+        let (bl, br) = (BigUint::from(l), BigUint::from(r));
+        let to_u128 =
+            |n: BigUint| u128::from_be_bytes(n.to_bytes_be_padded(16).unwrap().try_into().unwrap());
+        assert_eq!(to_u128(&bl + &br), l as u128 + r as u128);
+        assert_eq!(to_u128(&bl * &br), l as u128 * r as u128);
+        if let (Some(q), Some(rem)) = (l.checked_div(r), l.checked_rem(r)) {
+            assert_eq!(to_u128(&bl / &br), q as u128);
+            assert_eq!(to_u128(&bl % &br), rem as u128);
+        }
+        assert_eq!(
+            bl.checked_sub(&br).map(to_u128),
+            l.checked_sub(r).map(|d| d as u128)
+        );
+        assert_eq!(bl.cmp(&br), l.cmp(&r));
+    }
+}
+
+#[test]
+fn test_div_rem() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..500 {
+        let u = BigUint::from_bytes_be(&crate::aes::random_bytes(rng.gen_range(1..100)));
+        let v = BigUint::from_bytes_be(&crate::aes::random_bytes(rng.gen_range(1..60)));
+        if v.is_zero() {
+            continue;
+        }
+        let (q, r) = u.div_rem(&v);
+        assert!(r < v);
+        assert_eq!(&(&q * &v) + &r, u);
+    }
+
+    // exercises the add-back step, which random inputs almost never reach
+    // (from the Hacker's Delight divmnu test cases)
+    let u = BigUint::from_hex("800000000000000000000003").unwrap();
+    let v = BigUint::from_hex("200000000000000000000001").unwrap();
+    let (q, r) = u.div_rem(&v);
+    assert_eq!(q, BigUint::from(3u32));
+    assert_eq!(r.to_hex(), "200000000000000000000000");
+}
+
+#[test]
+fn test_conversions() -> MyResult<()> {
+    let hex = "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74";
+    let n = BigUint::from_hex(hex)?;
+    assert_eq!(n.to_hex(), hex);
+    assert_eq!(n.to_bytes_be().to_hex(), hex);
+    assert_eq!(BigUint::from_bytes_be(&from_hex(hex)?), n);
+    assert_eq!(BigUint::from_hex("00abc")?.to_hex(), "0abc");
+    for x in [0u64, 1, 0xabc, 0x1_0000_0000, u64::MAX] {
+        let x = BigUint::from(x);
+        assert!(x.to_hex().len().is_multiple_of(2));
+        assert_eq!(from_hex(&x.to_hex())?, x.to_bytes_be());
+        assert_eq!(BigUint::from_hex(&x.to_hex())?, x);
+    }
+    assert_eq!(BigUint::from(0xabcu32).to_hex(), "0abc");
+    assert_eq!(BigUint::zero().to_hex(), "");
+    assert!(BigUint::zero().to_bytes_be().is_empty());
+    assert_eq!(
+        BigUint::from(0x0102u32).to_bytes_be_padded(4),
+        Some(vec![0, 0, 1, 2])
+    );
+    assert_eq!(BigUint::from(0x0102u32).to_bytes_be_padded(1), None);
+
+    let two_128 = &BigUint::one() << 128;
+    assert_eq!(two_128.to_dec(), "340282366920938463463374607431768211456");
+    assert_eq!(
+        BigUint::from_dec("340282366920938463463374607431768211456")?,
+        two_128
+    );
+    assert_eq!(&two_128 >> 127, BigUint::from(2u32));
+    assert_eq!(two_128.bits(), 129);
+    assert!(BigUint::from_dec("12a").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_number_theory() -> MyResult<()> {
+    let n = |x: u64| BigUint::from(x);
+    assert_eq!(n(4).modpow(&n(13), &n(497)), n(445));
+    assert_eq!(n(17).modinv(&n(3120)), Some(n(2753)));
+    assert_eq!(n(6).modinv(&n(9)), None);
+    assert_eq!(n(462).gcd(&n(1071)), n(21));
+    assert_eq!(n(27).cbrt(), n(3));
+    assert_eq!(n(26).cbrt(), n(2));
+    assert_eq!(n(1 << 40).nth_root(4), n(1 << 10));
+
+    // 2^127 - 1 is prime, so Fermat's little theorem holds
+    let p = &(&BigUint::one() << 127) - &BigUint::one();
+    let a = BigUint::from_dec("123456789012345678901234567890")?;
+    assert_eq!(a.modpow(&(&p - &n(1)), &p), n(1));
+    let inv = a.modinv(&p).unwrap();
+    assert_eq!(&(&a * &inv) % &p, n(1));
+
+    let big = BigUint::from_hex("123456789abcdef0123456789abcdef0123456789abcdef")?;
+    let cube = big.pow(3);
+    assert_eq!(cube.cbrt(), big);
+    assert_eq!((&cube + &n(1)).cbrt(), big);
+    assert_eq!((&cube - &n(1)).cbrt(), &big - &n(1));
+    Ok(())
+}
+
+#[test]
+fn test_random_below() {
+    let mut rng = rand::thread_rng();
+    let bound = BigUint::from(1000u32);
+    let samples: Vec<BigUint> = (0..1000)
+        .map(|_| BigUint::random_below(&bound, &mut rng))
+        .collect();
+    assert!(samples.iter().all(|n| n < &bound));
+    assert!(samples.iter().any(|n| n >= &BigUint::from(900u32)));
+    assert!(samples.iter().any(|n| n < &BigUint::from(100u32)));
+}
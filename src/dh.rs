@@ -0,0 +1,80 @@
+use crate::{
+    aes::{random_bytes, AesError, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    bigint::BigUint,
+    hash::sha1,
+};
+
+// the 1536-bit MODP group from RFC 3526, as given in challenge 33
+const NIST_P_HEX: &str = "
+    ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DhParams {
+    pub p: BigUint,
+    pub g: BigUint,
+}
+
+impl DhParams {
+    pub fn nist() -> Self {
+        DhParams {
+            p: BigUint::from_hex(NIST_P_HEX).expect("NIST prime is valid hex"),
+            g: BigUint::from(2u32),
+        }
+    }
+}
+
+pub struct Keypair {
+    private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    pub fn generate(params: &DhParams) -> Self {
+        let private = BigUint::random_below(&params.p, &mut rand::thread_rng());
+        let public = params.g.modpow(&private, &params.p);
+        Keypair { private, public }
+    }
+
+    pub fn shared_secret(&self, params: &DhParams, other_public: &BigUint) -> BigUint {
+        other_public.modpow(&self.private, &params.p)
+    }
+}
+
+/**
+ * The AES-128 key for a shared secret: the first 16 bytes of its SHA-1
+ */
+pub fn derive_key(secret: &BigUint) -> Vec<u8> {
+    sha1(&secret.to_bytes_be())[..BLOCK_SIZE].to_vec()
+}
+
+/**
+ * AES-CBC under `key` with a random IV, which is appended to the ciphertext
+ */
+pub fn encrypt_message(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, AesError> {
+    let iv = random_bytes(BLOCK_SIZE);
+    let mut ciphertext = plaintext.encrypt(Mode::CBC, key, Some(&iv))?;
+    ciphertext.extend_from_slice(&iv);
+    Ok(ciphertext)
+}
+
+pub fn decrypt_message(key: &[u8], message: &[u8]) -> Result<Vec<u8>, AesError> {
+    if message.len() < BLOCK_SIZE {
+        return Err(AesError::InvalidCiphertextLength(message.len()));
+    }
+    let (ciphertext, iv) = message.split_at(message.len() - BLOCK_SIZE);
+    ciphertext.decrypt(Mode::CBC, key, Some(iv))
+}
+
+#[test]
+fn test_shared_secret() {
+    let params = DhParams::nist();
+    assert_eq!(params.p.bits(), 1536);
+    let a = Keypair::generate(&params);
+    let b = Keypair::generate(&params);
+    let s = a.shared_secret(&params, &b.public);
+    assert_eq!(s, b.shared_secret(&params, &a.public));
+
+    let key = derive_key(&s);
+    let message = encrypt_message(&key, b"hello bob").unwrap();
+    assert_eq!(decrypt_message(&key, &message).unwrap(), b"hello bob");
+}
@@ -0,0 +1,179 @@
+use crate::{bigint::BigUint, hash::sha1};
+use rand::Rng;
+
+// the domain parameters given in challenge 43
+const CRYPTOPALS_P_HEX: &str = "
+    800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65eac698c1702578b07dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc565f3bf6b50929139ebeac04f48c3c84afb796d61e5a4f9a8fda812ab59494232c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc871a584471bb1";
+const CRYPTOPALS_Q_HEX: &str = "f4f47f05794b256174bba6e9b396a7707e563c5b";
+const CRYPTOPALS_G_HEX: &str = "
+    5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa4046c8db53039db620c094c9fa077ef389b5322a559946a71903f990f1f7e0e025e2d7f7cf494aff1a0470f5b64c36b625a097f1651fe775323556fe00b3608c887892878480e99041be601a62166ca6894bdd41a7054ec89f756ba9fc95302291";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DsaParams {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl DsaParams {
+    pub fn cryptopals() -> Self {
+        DsaParams {
+            p: BigUint::from_hex(CRYPTOPALS_P_HEX).expect("p is valid hex"),
+            q: BigUint::from_hex(CRYPTOPALS_Q_HEX).expect("q is valid hex"),
+            g: BigUint::from_hex(CRYPTOPALS_G_HEX).expect("g is valid hex"),
+        }
+    }
+
+    /**
+     * y = g^x mod p
+     */
+    pub fn public_key(&self, private: &BigUint) -> BigUint {
+        self.g.modpow(private, &self.p)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/**
+ * The SHA-1 of the message as a number, which is what gets signed
+ */
+pub fn hash_message(message: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&sha1(message))
+}
+
+pub struct Keypair {
+    private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    /**
+     * A private key x in [1, q), drawn from `rng` so a seeded generator gives the
+     * same key
+     */
+    pub fn generate(params: &DsaParams, rng: &mut impl Rng) -> Self {
+        let one = BigUint::one();
+        let private = &BigUint::random_below(&(&params.q - &one), rng) + &one;
+        let public = params.public_key(&private);
+        Keypair { private, public }
+    }
+
+    /**
+     * Signs with a fresh nonce k in [1, q) from `rng`
+     */
+    pub fn sign(&self, params: &DsaParams, message: &[u8], rng: &mut impl Rng) -> Signature {
+        let one = BigUint::one();
+        loop {
+            let k = &BigUint::random_below(&(&params.q - &one), rng) + &one;
+            if let Some(signature) = self.sign_with_nonce(params, message, &k) {
+                return signature;
+            }
+        }
+    }
+
+    /**
+     * r = (g^k mod p) mod q, s = k^-1 (H(m) + xr) mod q, or None if either is zero
+     * and another k is needed
+     */
+    pub fn sign_with_nonce(
+        &self,
+        params: &DsaParams,
+        message: &[u8],
+        k: &BigUint,
+    ) -> Option<Signature> {
+        let r = &params.g.modpow(k, &params.p) % &params.q;
+        let k_inverse = k.modinv(&params.q)?;
+        let h = hash_message(message);
+        let s = &(&k_inverse * &(&h + &(&self.private * &r))) % &params.q;
+        if r.is_zero() || s.is_zero() {
+            return None;
+        }
+        Some(Signature { r, s })
+    }
+}
+
+/**
+ * Checks 0 < r < q and 0 < s < q, then that (g^u1 y^u2 mod p) mod q = r
+ */
+pub fn verify(params: &DsaParams, public: &BigUint, message: &[u8], signature: &Signature) -> bool {
+    let Signature { r, s } = signature;
+    if r.is_zero() || s.is_zero() || r >= &params.q || s >= &params.q {
+        return false;
+    }
+    verify_unchecked(params, public, message, signature)
+}
+
+/**
+ * The verification equation without the range checks on r and s, which is what lets
+ * tampered parameters through (challenge 45)
+ */
+pub fn verify_unchecked(
+    params: &DsaParams,
+    public: &BigUint,
+    message: &[u8],
+    signature: &Signature,
+) -> bool {
+    let Signature { r, s } = signature;
+    let w = match s.modinv(&params.q) {
+        Some(w) => w,
+        None => return false,
+    };
+    let u1 = &(&hash_message(message) * &w) % &params.q;
+    let u2 = &(r * &w) % &params.q;
+    let v = &(&params.g.modpow(&u1, &params.p) * &public.modpow(&u2, &params.p)) % &params.p;
+    &(&v % &params.q) == r
+}
+
+/**
+ * x = (sk - H(m)) / r mod q, for a signature whose nonce k is known
+ */
+pub fn private_key_from_nonce(
+    params: &DsaParams,
+    hash: &BigUint,
+    signature: &Signature,
+    k: &BigUint,
+) -> Option<BigUint> {
+    let q = &params.q;
+    let sk = &(&signature.s * k) % q;
+    let numerator = &(&sk + &(q - &(hash % q))) % q;
+    Some(&(&numerator * &signature.r.modinv(q)?) % q)
+}
+
+#[test]
+fn test_sign_verify() {
+    use crate::mt19937::Mt19937;
+
+    let params = DsaParams::cryptopals();
+    assert_eq!(params.p.bits(), 1024);
+    assert_eq!(params.q.bits(), 160);
+    assert!((&(&params.p - &BigUint::one()) % &params.q).is_zero());
+    assert!(params.g.modpow(&params.q, &params.p).is_one());
+
+    let mut rng = Mt19937::new(43);
+    let keypair = Keypair::generate(&params, &mut rng);
+    let signature = keypair.sign(&params, b"hi mom", &mut rng);
+    assert_ne!(signature, keypair.sign(&params, b"hi mom", &mut rng));
+    assert!(verify(&params, &keypair.public, b"hi mom", &signature));
+    assert!(!verify(&params, &keypair.public, b"hi dad", &signature));
+    let tampered = Signature {
+        r: signature.r.clone(),
+        s: &signature.s + &BigUint::one(),
+    };
+    assert!(!verify(&params, &keypair.public, b"hi mom", &tampered));
+}
+
+#[test]
+fn test_private_key_from_nonce() {
+    use crate::mt19937::Mt19937;
+
+    let params = DsaParams::cryptopals();
+    let keypair = Keypair::generate(&params, &mut Mt19937::new(43));
+    let k = BigUint::from(0x1234_5678u32);
+    let signature = keypair.sign_with_nonce(&params, b"hi mom", &k).unwrap();
+    let x = private_key_from_nonce(&params, &hash_message(b"hi mom"), &signature, &k).unwrap();
+    assert_eq!(x, keypair.private);
+}
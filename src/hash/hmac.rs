@@ -0,0 +1,74 @@
+use super::{sha1, sha256, MerkleDamgard, Sha1, Sha256, BLOCK_SIZE};
+
+// RFC 2104: https://www.rfc-editor.org/rfc/rfc2104
+pub fn hmac<H: MerkleDamgard>(key: &[u8], message: &[u8]) -> H::Digest {
+    let mut block_key = if key.len() > BLOCK_SIZE {
+        H::digest(key).as_ref().to_vec()
+    } else {
+        key.to_vec()
+    };
+    block_key.resize(BLOCK_SIZE, 0);
+
+    let mut inner = H::new();
+    inner.update(&block_key.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+    inner.update(message);
+    let inner = inner.finalize();
+
+    let mut outer = H::new();
+    outer.update(&block_key.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+    outer.update(inner.as_ref());
+    outer.finalize()
+}
+
+pub fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; sha1::DIGEST_SIZE] {
+    hmac::<Sha1>(key, message)
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; sha256::DIGEST_SIZE] {
+    hmac::<Sha256>(key, message)
+}
+
+#[test]
+fn test_rfc_2202_vectors() {
+    use crate::serializers::Serialize;
+
+    let vectors: [(&[u8], &[u8], &str); 4] = [
+        (
+            &[0x0b; 20],
+            b"Hi There",
+            "b617318655057264e28bc0b6fb378c8ef146be00",
+        ),
+        (
+            b"Jefe",
+            b"what do ya want for nothing?",
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+        ),
+        (
+            &[0xaa; 20],
+            &[0xdd; 50],
+            "125d7342b9ac11cd91a39af48aa17b4f63f175d3",
+        ),
+        (
+            &[0xaa; 80],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+        ),
+    ];
+    for (key, message, expected) in vectors {
+        assert_eq!(hmac_sha1(key, message).to_hex(), expected);
+    }
+}
+
+#[test]
+fn test_rfc_4231_vectors() {
+    use crate::serializers::Serialize;
+
+    assert_eq!(
+        hmac_sha256(&[0x0b; 20], b"Hi There").to_hex(),
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+    );
+    assert_eq!(
+        hmac_sha256(b"Jefe", b"what do ya want for nothing?").to_hex(),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
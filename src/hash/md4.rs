@@ -0,0 +1,158 @@
+use super::{buffer_blocks, md_padding_with, MerkleDamgard, BLOCK_SIZE};
+
+// RFC 1320: https://www.rfc-editor.org/rfc/rfc1320
+pub const DIGEST_SIZE: usize = 16;
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+#[derive(Clone)]
+pub struct Md4 {
+    state: [u32; 4],
+    // bytes waiting for a full block
+    buffer: Vec<u8>,
+    // total message length in bytes, including anything processed before from_state
+    len: u64,
+}
+
+impl MerkleDamgard for Md4 {
+    type State = [u32; 4];
+    type Digest = [u8; DIGEST_SIZE];
+
+    fn new() -> Self {
+        Md4::from_state(INITIAL_STATE, 0)
+    }
+
+    fn from_state(state: [u32; 4], len: u64) -> Self {
+        Md4 {
+            state,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            len,
+        }
+    }
+
+    fn state_from_digest(digest: &[u8; DIGEST_SIZE]) -> [u32; 4] {
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        state
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        let state = &mut self.state;
+        buffer_blocks(&mut self.buffer, data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let padding = md_padding(self.len);
+        self.update(&padding);
+        debug_assert!(self.buffer.is_empty());
+        let mut digest = [0; DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    fn md_padding(len: u64) -> Vec<u8> {
+        md_padding(len)
+    }
+}
+
+impl Default for Md4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// message word order and shift amounts for each of the three rounds
+const ROUNDS: [([usize; 16], [u32; 4], u32); 3] = [
+    (
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [3, 7, 11, 19],
+        0,
+    ),
+    (
+        [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+        [3, 5, 9, 13],
+        0x5a827999,
+    ),
+    (
+        [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+        [3, 9, 11, 15],
+        0x6ed9eba1,
+    ),
+];
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let mut x = [0u32; 16];
+    for (word, bytes) in x.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut r = *state;
+    for (round, (order, shifts, k)) in ROUNDS.iter().enumerate() {
+        let f = match round {
+            0 => |x: u32, y: u32, z: u32| (x & y) | (!x & z),
+            1 => |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z),
+            _ => |x: u32, y: u32, z: u32| x ^ y ^ z,
+        };
+        for (i, &word) in order.iter().enumerate() {
+            // each step updates a, d, c, b in turn, mixing in the other three in order
+            let t = (4 - i % 4) % 4;
+            r[t] = r[t]
+                .wrapping_add(f(r[(t + 1) % 4], r[(t + 2) % 4], r[(t + 3) % 4]))
+                .wrapping_add(x[word])
+                .wrapping_add(*k)
+                .rotate_left(shifts[i % 4]);
+        }
+    }
+
+    for (state, word) in state.iter_mut().zip(r) {
+        *state = state.wrapping_add(word);
+    }
+}
+
+/**
+ * Like SHA-1's padding, but the length in bits is a little-endian u64
+ */
+pub fn md_padding(len: u64) -> Vec<u8> {
+    md_padding_with(len, (len * 8).to_le_bytes())
+}
+
+pub fn md4(data: &[u8]) -> [u8; DIGEST_SIZE] {
+    Md4::digest(data)
+}
+
+#[test]
+fn test_rfc_1320_vectors() {
+    use crate::serializers::Serialize;
+
+    let vectors: [(&[u8], &str); 7] = [
+        (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+        (b"a", "bde52cb31de33e46245e05fbdbd6fb24"),
+        (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+        (b"message digest", "d9130a8164549fe818874806e1c7014b"),
+        (
+            b"abcdefghijklmnopqrstuvwxyz",
+            "d79e1c308aa5bbcdeea8ed63df412da9",
+        ),
+        (
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            "043f8582f241db351ce627e153e7f0e4",
+        ),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "e33b4ddc9c38f2199c3e7b164fcc0536",
+        ),
+    ];
+    for (input, expected) in vectors {
+        assert_eq!(md4(input).to_hex(), expected);
+    }
+}
+
+#[test]
+fn test_md_padding() {
+    assert_eq!(md_padding(3)[53..], 24u64.to_le_bytes());
+    assert_eq!(md_padding(56).len(), 72);
+}
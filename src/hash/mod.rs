@@ -0,0 +1,94 @@
+pub mod hmac;
+pub mod md4;
+pub mod sha1;
+pub mod sha256;
+
+pub use hmac::{hmac, hmac_sha1, hmac_sha256};
+pub use md4::{md4, Md4};
+pub use sha1::{sha1, Sha1};
+pub use sha256::{sha256, Sha256};
+
+/**
+ * A hash built from a compression function over 64-byte blocks, with the message
+ * length appended in the padding. The digest is the internal state after the padding,
+ * so hashing can continue from any digest: length extension works against all of them.
+ */
+pub trait MerkleDamgard: Sized {
+    type State;
+    type Digest: AsRef<[u8]> + PartialEq;
+
+    fn new() -> Self;
+
+    /**
+     * Continues a hash from its internal registers after `len` bytes have been
+     * processed. `len` includes the padding, so it's a multiple of the block size.
+     */
+    fn from_state(state: Self::State, len: u64) -> Self;
+
+    /**
+     * The internal registers that produced `digest`
+     */
+    fn state_from_digest(digest: &Self::Digest) -> Self::State;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Self::Digest;
+
+    /**
+     * The padding appended to a message of `len` bytes
+     */
+    fn md_padding(len: u64) -> Vec<u8>;
+
+    fn digest(data: &[u8]) -> Self::Digest {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /**
+     * hash(key || message), which is vulnerable to length extension (challenges 28 and 30)
+     */
+    fn secret_prefix_mac(key: &[u8], message: &[u8]) -> Self::Digest {
+        let mut hasher = Self::new();
+        hasher.update(key);
+        hasher.update(message);
+        hasher.finalize()
+    }
+}
+
+pub const BLOCK_SIZE: usize = 64;
+
+/**
+ * Buffers `data` into whole blocks and feeds them to `compress`, keeping any partial
+ * block in `buffer` for the next update
+ */
+fn buffer_blocks(buffer: &mut Vec<u8>, mut data: &[u8], mut compress: impl FnMut(&[u8])) {
+    if !buffer.is_empty() {
+        let take = (BLOCK_SIZE - buffer.len()).min(data.len());
+        buffer.extend_from_slice(&data[..take]);
+        data = &data[take..];
+        if buffer.len() < BLOCK_SIZE {
+            return;
+        }
+        compress(buffer);
+        buffer.clear();
+    }
+    let mut blocks = data.chunks_exact(BLOCK_SIZE);
+    for block in &mut blocks {
+        compress(block);
+    }
+    buffer.extend_from_slice(blocks.remainder());
+}
+
+/**
+ * A 1 bit, zeros up to 8 bytes short of a block boundary, then the message length
+ * in bits, already encoded by the caller
+ */
+fn md_padding_with(len: u64, length_bytes: [u8; 8]) -> Vec<u8> {
+    let block_size = BLOCK_SIZE as u64;
+    let zeros = (block_size * 2 - 9 - len % block_size) % block_size;
+    let mut padding = vec![0x80];
+    padding.resize(1 + zeros as usize, 0);
+    padding.extend_from_slice(&length_bytes);
+    padding
+}
@@ -0,0 +1,175 @@
+use super::{buffer_blocks, md_padding_with, MerkleDamgard, BLOCK_SIZE};
+
+// https://en.wikipedia.org/wiki/SHA-1
+// and FIPS 180-4: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+pub const DIGEST_SIZE: usize = 20;
+const INITIAL_STATE: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    // bytes waiting for a full block
+    buffer: Vec<u8>,
+    // total message length in bytes, including anything processed before from_state
+    len: u64,
+}
+
+impl MerkleDamgard for Sha1 {
+    type State = [u32; 5];
+    type Digest = [u8; DIGEST_SIZE];
+
+    fn new() -> Self {
+        Sha1::from_state(INITIAL_STATE, 0)
+    }
+
+    fn from_state(state: [u32; 5], len: u64) -> Self {
+        Sha1 {
+            state,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            len,
+        }
+    }
+
+    fn state_from_digest(digest: &[u8; DIGEST_SIZE]) -> [u32; 5] {
+        let mut state = [0; 5];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        state
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        let state = &mut self.state;
+        buffer_blocks(&mut self.buffer, data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let padding = md_padding(self.len);
+        self.update(&padding);
+        debug_assert!(self.buffer.is_empty());
+        let mut digest = [0; DIGEST_SIZE];
+        for (bytes, word) in digest.chunks_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn md_padding(len: u64) -> Vec<u8> {
+        md_padding(len)
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, &word) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5a827999),
+            20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (state, word) in state.iter_mut().zip([a, b, c, d, e]) {
+        *state = state.wrapping_add(word);
+    }
+}
+
+/**
+ * The padding appended to a message of `len` bytes: a 1 bit, zeros up to 8 bytes short
+ * of a block boundary, then the message length in bits as a big-endian u64
+ */
+pub fn md_padding(len: u64) -> Vec<u8> {
+    md_padding_with(len, (len * 8).to_be_bytes())
+}
+
+pub fn sha1(data: &[u8]) -> [u8; DIGEST_SIZE] {
+    Sha1::digest(data)
+}
+
+#[test]
+fn test_fips_180_vectors() {
+    use crate::serializers::Serialize;
+
+    assert_eq!(
+        sha1(b"abc").to_hex(),
+        "a9993e364706816aba3e25717850c26c9cd0d89d"
+    );
+    assert_eq!(
+        sha1(b"").to_hex(),
+        "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+    );
+    assert_eq!(
+        sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").to_hex(),
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+    );
+    let mut hasher = Sha1::new();
+    for _ in 0..1000 {
+        hasher.update(&[b'a'; 1000]);
+    }
+    assert_eq!(
+        hasher.finalize().to_hex(),
+        "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+    );
+}
+
+#[test]
+fn test_streaming_update() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    for split in [0, 1, 55, 56, 63, 64, 65, 500, 1000] {
+        let mut hasher = Sha1::new();
+        hasher.update(&data[..split]);
+        hasher.update(&data[split..]);
+        assert_eq!(hasher.finalize(), sha1(&data));
+    }
+}
+
+#[test]
+fn test_md_padding() {
+    for len in 0..200u64 {
+        let padding = md_padding(len);
+        assert_eq!((len + padding.len() as u64) % BLOCK_SIZE as u64, 0);
+        assert_eq!(padding[padding.len() - 8..], (len * 8).to_be_bytes());
+    }
+    assert_eq!(md_padding(55).len(), 9);
+    assert_eq!(md_padding(56).len(), 72);
+}
+
+#[test]
+fn test_from_state() {
+    // continuing from the registers of a padded message is the same as hashing the
+    // padded message followed by more data
+    let message = b"some message";
+    let mut padded = message.to_vec();
+    padded.extend_from_slice(&md_padding(message.len() as u64));
+    let mut hasher = Sha1::from_state(Sha1::state_from_digest(&sha1(message)), padded.len() as u64);
+    hasher.update(b"more data");
+    padded.extend_from_slice(b"more data");
+    assert_eq!(hasher.finalize(), sha1(&padded));
+}
@@ -64,23 +64,35 @@ fn count_chars(bytes: &[u8]) -> HashMap<char, usize> {
 }
 
 // compute mean-squared-error, see https://statisticsbyjim.com/regression/mean-squared-error-mse/
-fn compute_score(bytes: &[u8]) -> u32 {
+pub(crate) fn compute_score(bytes: &[u8]) -> u32 {
+    match mean_squared_error(bytes) {
+        Some(mse) => mse as u32,
+        None => u32::MAX,
+    }
+}
+
+/**
+ * The unrounded score, or None if the bytes can't be english text
+ */
+pub(crate) fn mean_squared_error(bytes: &[u8]) -> Option<f32> {
     if !bytes.is_ascii() {
-        return u32::MAX;
+        return None;
     }
     if bytes
         .iter()
         .any(|&b| (b.to_ascii_lowercase().is_ascii_control()) && b != b'\n')
     {
-        return u32::MAX;
+        return None;
     }
     let counts = count_chars(bytes);
     let len = bytes.len();
-    (EXPECTED_FREQUENCIES.iter().fold(0f32, |acc, &(ch, freq)| {
-        let expected = len as f32 * (freq / 100.0);
-        let &actual = counts.get(&ch).unwrap_or(&0);
-        acc + (expected - actual as f32).powi(2)
-    }) / len as f32) as u32
+    Some(
+        EXPECTED_FREQUENCIES.iter().fold(0f32, |acc, &(ch, freq)| {
+            let expected = len as f32 * (freq / 100.0);
+            let &actual = counts.get(&ch).unwrap_or(&0);
+            acc + (expected - actual as f32).powi(2)
+        }) / len as f32,
+    )
 }
 
 #[derive(Debug, PartialEq)]
@@ -190,7 +202,7 @@ fn test_transpose() {
     );
     assert_eq!(
         transpose_input(
-            &vec![1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8]
+            &[1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8]
                 .iter()
                 .cycle()
                 .take(35)
@@ -238,14 +250,25 @@ pub fn break_repeating_key_xor(input: &[u8]) -> Option<Vec<u8>> {
         if blocks.iter().any(|block| !block.is_ascii()) {
             return None;
         }
-        let mut key_bytes: Vec<u8> = Vec::new();
-        for block in blocks {
-            let res = break_single_byte_xor(&block).unwrap();
-            if res.score == u32::MAX {
-                return None;
-            }
-            key_bytes.push(res.key);
-        }
-        Some(key_bytes)
+        break_transposed(&blocks)
     })
 }
+
+/**
+ * Returns the key that breaks the input, when the key size is already known
+ */
+pub fn break_repeating_key_xor_with_keysize(input: &[u8], keysize: usize) -> Option<Vec<u8>> {
+    break_transposed(&transpose_input(input, keysize))
+}
+
+// breaks each block, every keysize-th byte of the input, as single-byte xor
+fn break_transposed(blocks: &[Vec<u8>]) -> Option<Vec<u8>> {
+    blocks
+        .iter()
+        .map(|block| {
+            break_single_byte_xor(block)
+                .filter(|res| res.score != u32::MAX)
+                .map(|res| res.key)
+        })
+        .collect()
+}
@@ -2,12 +2,24 @@
 extern crate lazy_static;
 
 pub mod aes;
+pub mod attacks;
+pub mod bigint;
+pub mod dh;
+pub mod dsa;
+pub mod hash;
 pub mod letter_frequency;
+pub mod mt19937;
+pub mod rsa;
 pub mod serializers;
+pub mod srp;
 pub mod xor;
 
 pub mod set1;
 pub mod set2;
+pub mod set3;
+pub mod set4;
+pub mod set5;
+pub mod set6;
 
 pub type MyResult<T> = Result<T, Box<dyn std::error::Error>>;
 
@@ -1,5 +1,9 @@
 use cryptopals_rs::set1;
 use cryptopals_rs::set2;
+use cryptopals_rs::set3;
+use cryptopals_rs::set4;
+use cryptopals_rs::set5;
+use cryptopals_rs::set6;
 use cryptopals_rs::MyResult;
 
 fn main() -> MyResult<()> {
@@ -25,5 +29,75 @@ fn main() -> MyResult<()> {
     println!("-----------------------");
     set2::challenge11()?;
     println!("-----------------------");
+    set2::challenge12()?;
+    println!("-----------------------");
+    set2::challenge13()?;
+    println!("-----------------------");
+    set2::challenge14()?;
+    println!("-----------------------");
+    set2::challenge15();
+    println!("-----------------------");
+    set2::challenge16()?;
+    println!("-----------------------");
+    set3::challenge17()?;
+    println!("-----------------------");
+    set3::challenge18()?;
+    println!("-----------------------");
+    set3::challenge19()?;
+    println!("-----------------------");
+    set3::challenge20()?;
+    println!("-----------------------");
+    set3::challenge21();
+    println!("-----------------------");
+    set3::challenge22();
+    println!("-----------------------");
+    set3::challenge23();
+    println!("-----------------------");
+    set3::challenge24()?;
+    println!("-----------------------");
+    set4::challenge25()?;
+    println!("-----------------------");
+    set4::challenge26()?;
+    println!("-----------------------");
+    set4::challenge27()?;
+    println!("-----------------------");
+    set4::challenge28();
+    println!("-----------------------");
+    set4::challenge29()?;
+    println!("-----------------------");
+    set4::challenge30()?;
+    println!("-----------------------");
+    set4::challenge31()?;
+    println!("-----------------------");
+    set4::challenge32()?;
+    println!("-----------------------");
+    set5::challenge33();
+    println!("-----------------------");
+    set5::challenge34()?;
+    println!("-----------------------");
+    set5::challenge35()?;
+    println!("-----------------------");
+    set5::challenge36()?;
+    println!("-----------------------");
+    set5::challenge37()?;
+    println!("-----------------------");
+    set5::challenge38()?;
+    println!("-----------------------");
+    set5::challenge39()?;
+    println!("-----------------------");
+    set5::challenge40()?;
+    println!("-----------------------");
+    set6::challenge41()?;
+    println!("-----------------------");
+    set6::challenge42()?;
+    println!("-----------------------");
+    set6::challenge43()?;
+    println!("-----------------------");
+    set6::challenge44()?;
+    println!("-----------------------");
+    set6::challenge45()?;
+    println!("-----------------------");
+    set6::challenge46()?;
+    println!("-----------------------");
     Ok(())
 }
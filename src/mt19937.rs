@@ -0,0 +1,379 @@
+use crate::xor::Xor;
+use rand::{Error, RngCore};
+
+// https://en.wikipedia.org/wiki/Mersenne_Twister
+// and the reference implementations:
+//  - http://www.math.sci.hiroshima-u.ac.jp/m-mat/MT/MT2002/CODES/mt19937ar.c
+//  - http://www.math.sci.hiroshima-u.ac.jp/m-mat/MT/VERSIONS/C-LANG/mt19937-64.c
+pub const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908b0df;
+const UPPER_MASK: u32 = 0x80000000;
+const LOWER_MASK: u32 = 0x7fffffff;
+pub const DEFAULT_SEED: u32 = 5489;
+
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0u32; N];
+        state[0] = seed;
+        for i in 1..N {
+            state[i] = 1812433253u32
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { state, index: N }
+    }
+
+    /**
+     * init_by_array in the reference implementation, which needs at least one word of
+     * key, so an empty key gives None
+     */
+    pub fn from_key(key: &[u32]) -> Option<Self> {
+        if key.is_empty() {
+            return None;
+        }
+        let mut mt = Mt19937::new(19650218);
+        let state = &mut mt.state;
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..N.max(key.len()) {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1566083941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+        state[0] = UPPER_MASK;
+        Some(mt)
+    }
+
+    /**
+     * A generator that continues from `state`, which must hold 624 consecutive
+     * untempered values. The next output is generated by twisting the whole state.
+     */
+    pub fn from_state(state: [u32; N]) -> Self {
+        Mt19937 { state, index: N }
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mut next = self.state[(i + M) % N] ^ (y >> 1);
+            if y & 1 != 0 {
+                next ^= MATRIX_A;
+            }
+            self.state[i] = next;
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+impl Default for Mt19937 {
+    fn default() -> Self {
+        Mt19937::new(DEFAULT_SEED)
+    }
+}
+
+pub fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c5680;
+    y ^= (y << 15) & 0xefc60000;
+    y ^= y >> 18;
+    y
+}
+
+// inverts y ^= (y >> shift)
+fn invert_right_shift(y: u32, shift: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..(32 / shift) {
+        x = y ^ (x >> shift);
+    }
+    x
+}
+
+// inverts y ^= (y << shift) & mask
+fn invert_left_shift(y: u32, shift: u32, mask: u32) -> u32 {
+    let mut x = y;
+    for _ in 0..(32 / shift) {
+        x = y ^ ((x << shift) & mask);
+    }
+    x
+}
+
+/**
+ * Inverts `temper`, recovering the state word that produced an output. Each step of
+ * the tempering only mixes in bits from one direction, so the known bits at one end
+ * determine the next `shift` bits, and so on.
+ */
+pub fn untemper(mut y: u32) -> u32 {
+    y = invert_right_shift(y, 18);
+    y = invert_left_shift(y, 15, 0xefc60000);
+    y = invert_left_shift(y, 7, 0x9d2c5680);
+    y = invert_right_shift(y, 11);
+    y
+}
+
+#[test]
+fn test_untemper() {
+    let mut mt = Mt19937::new(1234);
+    for _ in 0..1000 {
+        let y = mt.next_u32();
+        assert_eq!(temper(untemper(y)), y);
+        assert_eq!(untemper(temper(y)), y);
+    }
+}
+
+impl RngCore for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        Mt19937::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let lo = Mt19937::next_u32(self) as u64;
+        let hi = Mt19937::next_u32(self) as u64;
+        (hi << 32) | lo
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = Mt19937::next_u32(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/**
+ * `len` bytes of keystream from a generator seeded with `seed`, taking the bytes of
+ * each output in little-endian order
+ */
+pub fn keystream(seed: u32, len: usize) -> Vec<u8> {
+    let mut keystream = vec![0; len];
+    Mt19937::new(seed).fill_bytes(&mut keystream);
+    keystream
+}
+
+/**
+ * The challenge 24 stream cipher: XORs `data` with the keystream of a generator
+ * seeded with a 16-bit key. Encryption and decryption are the same operation.
+ */
+pub fn apply_keystream(seed: u16, data: &[u8]) -> Vec<u8> {
+    data.xor(&keystream(seed as u32, data.len()))
+}
+
+#[test]
+fn test_apply_keystream() {
+    let plaintext = b"a stream cipher keyed by a 16-bit seed";
+    let ciphertext = apply_keystream(0x1234, plaintext);
+    assert_eq!(ciphertext.len(), plaintext.len());
+    assert_ne!(&ciphertext, plaintext);
+    assert_eq!(apply_keystream(0x1234, &ciphertext), plaintext);
+    assert_ne!(apply_keystream(0x1235, &ciphertext), plaintext);
+}
+
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A_64: u64 = 0xb5026f5aa96619e9;
+const UPPER_MASK_64: u64 = 0xffffffff80000000;
+const LOWER_MASK_64: u64 = 0x7fffffff;
+pub const DEFAULT_SEED_64: u64 = 5489;
+
+/**
+ * The 64-bit variant, MT19937-64. It has the same structure as MT19937 with a
+ * 312-word state and different constants.
+ */
+pub struct Mt19937_64 {
+    state: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    pub fn new(seed: u64) -> Self {
+        let mut state = [0u64; NN];
+        state[0] = seed;
+        for i in 1..NN {
+            state[i] = 6364136223846793005u64
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 62))
+                .wrapping_add(i as u64);
+        }
+        Mt19937_64 { state, index: NN }
+    }
+
+    /**
+     * init_by_array64 in the reference implementation, which also needs a non-empty key
+     */
+    pub fn from_key(key: &[u64]) -> Option<Self> {
+        if key.is_empty() {
+            return None;
+        }
+        let mut mt = Mt19937_64::new(19650218);
+        let state = &mut mt.state;
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..NN.max(key.len()) {
+            state[i] = (state[i]
+                ^ (state[i - 1] ^ (state[i - 1] >> 62)).wrapping_mul(3935559000370003845))
+            .wrapping_add(key[j])
+            .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..NN - 1 {
+            state[i] = (state[i]
+                ^ (state[i - 1] ^ (state[i - 1] >> 62)).wrapping_mul(2862933555777941757))
+            .wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+        }
+        state[0] = 1 << 63;
+        Some(mt)
+    }
+
+    fn twist(&mut self) {
+        for i in 0..NN {
+            let y = (self.state[i] & UPPER_MASK_64) | (self.state[(i + 1) % NN] & LOWER_MASK_64);
+            let mut next = self.state[(i + MM) % NN] ^ (y >> 1);
+            if y & 1 != 0 {
+                next ^= MATRIX_A_64;
+            }
+            self.state[i] = next;
+        }
+        self.index = 0;
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let mut y = self.state[self.index];
+        self.index += 1;
+        y ^= (y >> 29) & 0x5555555555555555;
+        y ^= (y << 17) & 0x71d67fffeda60000;
+        y ^= (y << 37) & 0xfff7eee000000000;
+        y ^= y >> 43;
+        y
+    }
+}
+
+impl Default for Mt19937_64 {
+    fn default() -> Self {
+        Mt19937_64::new(DEFAULT_SEED_64)
+    }
+}
+
+impl RngCore for Mt19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        (Mt19937_64::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Mt19937_64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = Mt19937_64::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_mt19937_reference_outputs() {
+    // The C++ standard requires the 10000th output of a default-constructed
+    // std::mt19937 to be 4123659995
+    let mut mt = Mt19937::default();
+    assert_eq!(mt.next_u32(), 3499211612);
+    let output = (1..10000).map(|_| mt.next_u32()).last();
+    assert_eq!(output, Some(4123659995));
+
+    // mt19937ar.out, from init_by_array({0x123, 0x234, 0x345, 0x456})
+    let mut mt = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]).unwrap();
+    let outputs: Vec<u32> = (0..5).map(|_| mt.next_u32()).collect();
+    assert_eq!(
+        outputs,
+        [1067595299, 955945823, 477289528, 4107218783, 4228976476]
+    );
+    assert!(Mt19937::from_key(&[]).is_none());
+}
+
+#[test]
+fn test_mt19937_64_reference_outputs() {
+    // ... and of a default-constructed std::mt19937_64 to be 9981545732273789042
+    let mut mt = Mt19937_64::default();
+    let output = (0..10000).map(|_| mt.next_u64()).last();
+    assert_eq!(output, Some(9981545732273789042));
+
+    // mt19937-64.out.txt, from init_by_array64({0x12345, 0x23456, 0x34567, 0x45678})
+    let mut mt = Mt19937_64::from_key(&[0x12345, 0x23456, 0x34567, 0x45678]).unwrap();
+    let outputs: Vec<u64> = (0..5).map(|_| mt.next_u64()).collect();
+    assert_eq!(
+        outputs,
+        [
+            7266447313870364031,
+            4946485549665804864,
+            16945909448695747420,
+            16394063075524226720,
+            4873882236456199058
+        ]
+    );
+    assert!(Mt19937_64::from_key(&[]).is_none());
+}
+
+#[test]
+fn test_rng_core() {
+    use rand::Rng;
+
+    let mut l = Mt19937::new(42);
+    let mut r = Mt19937::new(42);
+    let l: Vec<u8> = (0..100).map(|_| l.gen()).collect();
+    let r: Vec<u8> = (0..100).map(|_| r.gen()).collect();
+    assert_eq!(l, r);
+}
@@ -0,0 +1,282 @@
+use crate::{
+    bigint::BigUint,
+    hash::{sha1, sha1::DIGEST_SIZE},
+    MyResult,
+};
+use rand::Rng;
+
+/**
+ * The DER encoding of a DigestInfo for SHA-1, up to the digest itself (RFC 8017 §9.2)
+ */
+pub const SHA1_DIGEST_INFO: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+const MILLER_RABIN_ROUNDS: usize = 40;
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+pub fn invmod(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    a.modinv(modulus)
+}
+
+/**
+ * Trial division by a few small primes, then Miller-Rabin with random bases
+ */
+pub fn is_probable_prime(n: &BigUint, rng: &mut impl Rng) -> bool {
+    for p in SMALL_PRIMES {
+        let p = BigUint::from(p);
+        if n == &p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+    if n < &BigUint::from(2u32) {
+        return false;
+    }
+
+    // n - 1 = 2^s * d with d odd
+    let one = BigUint::one();
+    let n_minus_1 = n - &one;
+    let s = (0..).find(|&bit| n_minus_1.bit(bit)).unwrap();
+    let d = &n_minus_1 >> s;
+    let two = BigUint::from(2u32);
+    let base_range = n - &BigUint::from(3u32);
+    'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+        let a = &BigUint::random_below(&base_range, rng) + &two;
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/**
+ * A random prime with exactly `bits` bits, the top two of them set, so that the
+ * product of two such primes always has exactly as many bits as the two together
+ */
+pub fn generate_prime(bits: usize, rng: &mut impl Rng) -> BigUint {
+    assert!(bits >= 8, "primes must have at least 8 bits");
+    let top = &BigUint::one() << (bits - 1);
+    let quarter = &BigUint::one() << (bits - 2);
+    let top_two = &top + &quarter;
+    loop {
+        // the random part is below 2^(bits - 2), so the result can't overflow when made odd
+        let mut candidate = &BigUint::random_below(&quarter, rng) + &top_two;
+        if candidate.is_even() {
+            candidate = &candidate + &BigUint::one();
+        }
+        if is_probable_prime(&candidate, rng) {
+            return candidate;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey {
+    pub e: BigUint,
+    pub n: BigUint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivateKey {
+    pub d: BigUint,
+    pub n: BigUint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keypair {
+    pub public: PublicKey,
+    pub private: PrivateKey,
+}
+
+impl Keypair {
+    /**
+     * Generates primes of `bits / 2` bits until e is invertible mod (p - 1)(q - 1).
+     * All randomness comes from `rng`, so a seeded generator gives the same key.
+     */
+    pub fn generate(bits: usize, e: u32, rng: &mut impl Rng) -> Self {
+        let e = BigUint::from(e);
+        let one = BigUint::one();
+        loop {
+            let p = generate_prime(bits / 2, rng);
+            let q = generate_prime(bits - bits / 2, rng);
+            if p == q {
+                continue;
+            }
+            let et = &(&p - &one) * &(&q - &one);
+            if let Some(d) = invmod(&e, &et) {
+                let n = &p * &q;
+                return Keypair {
+                    public: PublicKey { e, n: n.clone() },
+                    private: PrivateKey { d, n },
+                };
+            }
+        }
+    }
+}
+
+/**
+ * EMSA-PKCS1-v1_5 with SHA-1: 00 01 ff .. ff 00 DigestInfo || SHA1(message), filling
+ * `len` bytes
+ */
+pub fn pkcs1_v15_encode(message: &[u8], len: usize) -> MyResult<Vec<u8>> {
+    let suffix_len = 1 + SHA1_DIGEST_INFO.len() + DIGEST_SIZE;
+    // at least 8 bytes of 0xff padding
+    if len < suffix_len + 10 {
+        return Err("modulus is too short for a pkcs#1 v1.5 sha-1 signature".into());
+    }
+    let mut encoded = vec![0x00, 0x01];
+    encoded.resize(len - suffix_len, 0xff);
+    encoded.push(0x00);
+    encoded.extend_from_slice(&SHA1_DIGEST_INFO);
+    encoded.extend_from_slice(&sha1(message));
+    Ok(encoded)
+}
+
+impl PublicKey {
+    /**
+     * The length of the modulus in bytes
+     */
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    /**
+     * Checks a PKCS#1 v1.5 SHA-1 signature by re-encoding the message and comparing
+     * the whole block
+     */
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        let s = BigUint::from_bytes_be(signature);
+        if s >= self.n {
+            return false;
+        }
+        let encoded = self.encrypt_int(&s).to_bytes_be_padded(self.size());
+        match (encoded, pkcs1_v15_encode(message, self.size())) {
+            (Some(encoded), Ok(expected)) => encoded == expected,
+            _ => false,
+        }
+    }
+
+    pub fn encrypt_int(&self, m: &BigUint) -> BigUint {
+        m.modpow(&self.e, &self.n)
+    }
+
+    /**
+     * Textbook RSA with no padding. The message, read as a big-endian number, must be
+     * less than n.
+     */
+    pub fn encrypt(&self, message: &[u8]) -> MyResult<Vec<u8>> {
+        let m = BigUint::from_bytes_be(message);
+        if m >= self.n {
+            return Err("message is too large for the modulus".into());
+        }
+        Ok(self.encrypt_int(&m).to_bytes_be())
+    }
+}
+
+impl PrivateKey {
+    /**
+     * A PKCS#1 v1.5 SHA-1 signature, as many bytes long as the modulus
+     */
+    pub fn sign(&self, message: &[u8]) -> MyResult<Vec<u8>> {
+        let len = self.n.bits().div_ceil(8);
+        let encoded = BigUint::from_bytes_be(&pkcs1_v15_encode(message, len)?);
+        Ok(self
+            .decrypt_int(&encoded)
+            .to_bytes_be_padded(len)
+            .expect("signature is less than the modulus"))
+    }
+
+    pub fn decrypt_int(&self, c: &BigUint) -> BigUint {
+        c.modpow(&self.d, &self.n)
+    }
+
+    /**
+     * Leading zero bytes of the original message are lost, since it's treated as a number
+     */
+    pub fn decrypt(&self, ciphertext: &[u8]) -> MyResult<Vec<u8>> {
+        let c = BigUint::from_bytes_be(ciphertext);
+        if c >= self.n {
+            return Err("ciphertext is too large for the modulus".into());
+        }
+        Ok(self.decrypt_int(&c).to_bytes_be())
+    }
+}
+
+#[test]
+fn test_is_probable_prime() {
+    use crate::mt19937::Mt19937;
+
+    let mut rng = Mt19937::new(39);
+    let primes = [2u64, 3, 97, 7919, 2147483647, 18446744073709551557];
+    for p in primes {
+        assert!(is_probable_prime(&BigUint::from(p), &mut rng), "{}", p);
+    }
+    // 561 and 41041 are Carmichael numbers, which fool the Fermat test
+    let composites = [0u64, 1, 4, 561, 41041, 2147483649, 18446744073709551555];
+    for c in composites {
+        assert!(!is_probable_prime(&BigUint::from(c), &mut rng), "{}", c);
+    }
+    let mersenne = &(&BigUint::one() << 127) - &BigUint::one();
+    assert!(is_probable_prime(&mersenne, &mut rng));
+    assert!(!is_probable_prime(
+        &(&mersenne + &BigUint::from(2u32)),
+        &mut rng
+    ));
+}
+
+#[test]
+fn test_keygen_is_reproducible() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    let keypair = Keypair::generate(256, 3, &mut Mt19937::new(40));
+    assert_eq!(keypair, Keypair::generate(256, 3, &mut Mt19937::new(40)));
+    assert_ne!(keypair, Keypair::generate(256, 3, &mut Mt19937::new(41)));
+    for seed in 0..10 {
+        let mut rng = Mt19937::new(seed);
+        assert_eq!(generate_prime(64, &mut rng).bits(), 64);
+        assert!(generate_prime(64, &mut rng).bit(62));
+        for bits in [255, 256] {
+            let keypair = Keypair::generate(bits, 3, &mut rng);
+            assert_eq!(keypair.public.n.bits(), bits);
+        }
+    }
+
+    let ciphertext = keypair.public.encrypt(b"attack at dawn")?;
+    assert_eq!(keypair.private.decrypt(&ciphertext)?, b"attack at dawn");
+    assert!(keypair.public.encrypt(&[0xff; 33]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sign() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    let keypair = Keypair::generate(512, 65537, &mut Mt19937::new(42));
+    let signature = keypair.private.sign(b"hi mom")?;
+    assert_eq!(signature.len(), keypair.public.size());
+    assert!(keypair.public.verify(b"hi mom", &signature));
+    assert!(!keypair.public.verify(b"hi dad", &signature));
+
+    let encoded = pkcs1_v15_encode(b"hi mom", 64)?;
+    assert_eq!(
+        encoded[..11],
+        [0, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+    );
+    assert_eq!(encoded[28], 0);
+    assert_eq!(encoded[29..44], SHA1_DIGEST_INFO[..]);
+    assert!(pkcs1_v15_encode(b"hi mom", 45).is_err());
+    Ok(())
+}
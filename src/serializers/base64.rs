@@ -76,7 +76,7 @@ pub fn from_base64(s: &str) -> MyResult<Vec<u8>> {
         return Err("Base64 string must be ascii".into());
     }
 
-    if s.len() % 4 != 0 {
+    if !s.len().is_multiple_of(4) {
         return Err(format!(
             "Base64 string len must be divisible by 4, but got len {}",
             s.len()
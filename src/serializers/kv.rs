@@ -0,0 +1,46 @@
+use crate::MyResult;
+
+/**
+ * Parses a `foo=bar&baz=qux` style cookie into its key/value pairs, in order.
+ */
+pub fn parse(s: &str) -> MyResult<Vec<(String, String)>> {
+    s.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) => Ok((k.to_string(), v.to_string())),
+            None => Err(format!("kv pair is missing '=': {}", pair).into()),
+        })
+        .collect()
+}
+
+pub fn encode(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+pub fn get<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn test_parse() -> MyResult<()> {
+    let pairs = parse("foo=bar&baz=qux&zap=zazzle")?;
+    assert_eq!(
+        pairs,
+        [
+            ("foo".to_string(), "bar".to_string()),
+            ("baz".to_string(), "qux".to_string()),
+            ("zap".to_string(), "zazzle".to_string()),
+        ]
+    );
+    assert_eq!(get(&pairs, "baz"), Some("qux"));
+    assert_eq!(get(&pairs, "nope"), None);
+    assert_eq!(encode(&pairs), "foo=bar&baz=qux&zap=zazzle");
+    assert!(parse("foo=bar&baz").is_err());
+    Ok(())
+}
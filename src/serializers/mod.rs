@@ -2,6 +2,7 @@ use crate::{utils::read_file_to_string, MyResult};
 use std::path::Path;
 
 pub mod base64;
+pub mod kv;
 
 pub trait Serialize {
     fn to_hex(&self) -> String;
@@ -9,7 +10,7 @@ pub trait Serialize {
 }
 
 pub fn from_hex(s: &str) -> MyResult<Vec<u8>> {
-    if s.len() % 2 != 0 {
+    if !s.len().is_multiple_of(2) {
         return Err("Hex string must be even length".into());
     }
     let mut digits = Vec::new();
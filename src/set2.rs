@@ -1,8 +1,13 @@
 use std::path::Path;
 
 use crate::{
-    aes::{detect_ecb11, pkcs7_pad, Decrypt, Mode, Oracle},
-    serializers::base64,
+    aes::{detect_ecb11, pkcs7_pad, pkcs7_unpad, Decrypt, Mode, Oracle, SuffixOracle},
+    attacks::{
+        bitflipping::{cbc_forge_admin, CommentOracle},
+        ecb_byte_at_a_time::{detect_block_size, detect_ecb, recover_suffix},
+        ecb_cut_and_paste::{forge_admin_profile, ProfileService},
+    },
+    serializers::{base64, kv},
     MyResult,
 };
 
@@ -21,7 +26,7 @@ fn test_challenge9() {
 pub fn challenge10() -> MyResult<()> {
     println!("SET 2 CHALLENGE 10");
     let ciphertext = base64::from_file(Path::new("data/challenge10.txt"))?;
-    let iv = &vec![0; 16];
+    let iv = &[0; 16];
     let key = b"YELLOW SUBMARINE";
     let result = ciphertext.decrypt(Mode::CBC, key, Some(iv))?;
     println!("{:?}", result);
@@ -33,7 +38,7 @@ pub fn challenge10() -> MyResult<()> {
 fn test_challenge10() -> MyResult<()> {
     use crate::utils::read_file_to_string;
     let ciphertext = base64::from_file(Path::new("data/challenge10.txt"))?;
-    let iv = &vec![0; 16];
+    let iv = &[0; 16];
     let key = b"YELLOW SUBMARINE";
     let result = ciphertext.decrypt(Mode::CBC, key, Some(iv))?;
     let result = String::from_utf8_lossy(&result);
@@ -63,3 +68,126 @@ fn test_challenge11() -> MyResult<()> {
     }
     Ok(())
 }
+
+const CHALLENGE_12_SUFFIX: &str = "Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK";
+
+pub fn challenge12() -> MyResult<()> {
+    println!("SET 2 CHALLENGE 12");
+    let oracle = SuffixOracle::new(&base64::from_base64(CHALLENGE_12_SUFFIX)?);
+    let encrypt = |input: &[u8]| oracle.encrypt(input);
+    let block_size = detect_block_size(&encrypt);
+    println!(
+        "block size {}, ecb {}",
+        block_size,
+        detect_ecb(&encrypt, block_size)
+    );
+    let suffix = recover_suffix(encrypt)?;
+    oracle.verify(&suffix)?;
+    println!("{}", String::from_utf8_lossy(&suffix));
+    Ok(())
+}
+
+#[test]
+fn test_challenge12() -> MyResult<()> {
+    let oracle = SuffixOracle::new(&base64::from_base64(CHALLENGE_12_SUFFIX)?);
+    let encrypt = |input: &[u8]| oracle.encrypt(input);
+    assert_eq!(detect_block_size(&encrypt), 16);
+    assert!(detect_ecb11(&encrypt(&[0; 16 * 3])));
+    let suffix = recover_suffix(encrypt)?;
+    assert!(suffix.starts_with(b"Rollin' in my 5.0\n"));
+    oracle.verify(&suffix)
+}
+
+pub fn challenge13() -> MyResult<()> {
+    println!("SET 2 CHALLENGE 13");
+    let service = ProfileService::new();
+    let forged = forge_admin_profile(|email| service.encrypted_profile_for(email));
+    println!("{}", kv::encode(&service.decrypt_profile(&forged)?));
+    println!("is admin: {}", service.is_admin(&forged)?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge13() -> MyResult<()> {
+    let service = ProfileService::new();
+    let forged = forge_admin_profile(|email| service.encrypted_profile_for(email));
+    assert!(service.is_admin(&forged)?);
+    let profile = service.decrypt_profile(&forged)?;
+    assert_eq!(kv::get(&profile, "uid"), Some("10"));
+    Ok(())
+}
+
+pub fn challenge14() -> MyResult<()> {
+    println!("SET 2 CHALLENGE 14");
+    let oracle = SuffixOracle::with_random_prefix(&base64::from_base64(CHALLENGE_12_SUFFIX)?);
+    let suffix = recover_suffix(|input: &[u8]| oracle.encrypt(input))?;
+    oracle.verify(&suffix)?;
+    println!("{}", String::from_utf8_lossy(&suffix));
+    Ok(())
+}
+
+#[test]
+fn test_challenge14() -> MyResult<()> {
+    for _ in 0..5 {
+        let oracle = SuffixOracle::with_random_prefix(&base64::from_base64(CHALLENGE_12_SUFFIX)?);
+        let suffix = recover_suffix(|input: &[u8]| oracle.encrypt(input))?;
+        oracle.verify(&suffix)?;
+    }
+    Ok(())
+}
+
+pub fn challenge15() {
+    println!("SET 2 CHALLENGE 15");
+    let inputs: [&[u8]; 3] = [
+        b"ICE ICE BABY\x04\x04\x04\x04",
+        b"ICE ICE BABY\x05\x05\x05\x05",
+        b"ICE ICE BABY\x01\x02\x03\x04",
+    ];
+    for input in inputs {
+        match pkcs7_unpad(input, 16) {
+            Ok(unpadded) => println!("{:?} -> {:?}", input, String::from_utf8_lossy(&unpadded)),
+            Err(e) => println!("{:?} -> {}", input, e),
+        }
+    }
+}
+
+#[test]
+fn test_challenge15() {
+    use crate::aes::PaddingError;
+    assert_eq!(
+        pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16),
+        Ok(b"ICE ICE BABY".to_vec())
+    );
+    assert_eq!(
+        pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16),
+        Err(PaddingError::InconsistentPadBytes {
+            expected: 5,
+            found: b'Y'
+        })
+    );
+    assert_eq!(
+        pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+        Err(PaddingError::InconsistentPadBytes {
+            expected: 4,
+            found: 3
+        })
+    );
+}
+
+pub fn challenge16() -> MyResult<()> {
+    println!("SET 2 CHALLENGE 16");
+    let oracle = CommentOracle::new_cbc();
+    let forged = cbc_forge_admin(|userdata| oracle.encrypt(userdata));
+    println!("{}", String::from_utf8_lossy(&oracle.decrypt(&forged)?));
+    println!("is admin: {}", oracle.is_admin(&forged)?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge16() -> MyResult<()> {
+    let oracle = CommentOracle::new_cbc();
+    assert!(!oracle.is_admin(&oracle.encrypt(b";admin=true;"))?);
+    let forged = cbc_forge_admin(|userdata| oracle.encrypt(userdata));
+    assert!(oracle.is_admin(&forged)?);
+    Ok(())
+}
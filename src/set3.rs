@@ -0,0 +1,313 @@
+use crate::{
+    aes::{random_bytes, CtrLayout, Decrypt, Encrypt, Mode, BLOCK_SIZE},
+    attacks::{
+        fixed_nonce_ctr::recover_keystream,
+        mt19937_recovery::{
+            clone_from_outputs, crack_timestamp_seed, is_mt_token, password_reset_token,
+            recover_stream_key,
+        },
+        padding_oracle::{padding_oracle_attack, PaddingOracleService},
+    },
+    mt19937::{Mt19937, N},
+    serializers::base64::{self, from_base64},
+    utils::read_file_to_string,
+    MyResult,
+};
+use rand::Rng;
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub fn challenge17() -> MyResult<()> {
+    println!("SET 3 CHALLENGE 17");
+    let service = PaddingOracleService::new()?;
+    let (iv, ciphertext) = service.encrypt_random();
+    let result = padding_oracle_attack(&iv, &ciphertext, |iv, ciphertext| {
+        service.has_valid_padding(iv, ciphertext)
+    })?;
+    println!(
+        "{} ({} oracle queries)",
+        String::from_utf8_lossy(&result.plaintext),
+        result.total_queries()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_challenge17() -> MyResult<()> {
+    let service = PaddingOracleService::new()?;
+    assert_eq!(service.plaintexts().len(), 10);
+    for idx in 0..service.plaintexts().len() {
+        let (iv, ciphertext) = service.encrypt(idx);
+        let result = padding_oracle_attack(&iv, &ciphertext, |iv, ciphertext| {
+            service.has_valid_padding(iv, ciphertext)
+        })?;
+        assert_eq!(result.plaintext, service.plaintexts()[idx]);
+        assert_eq!(result.queries_per_byte.len(), ciphertext.len());
+    }
+    Ok(())
+}
+
+const CHALLENGE_18_INPUT: &str =
+    "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==";
+
+fn solve_challenge18() -> MyResult<String> {
+    let ciphertext = from_base64(CHALLENGE_18_INPUT)?;
+    let key = b"YELLOW SUBMARINE";
+    let nonce = [0; 8];
+    let plaintext = ciphertext.decrypt(Mode::CTR(CtrLayout::CRYPTOPALS), key, Some(&nonce))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+pub fn challenge18() -> MyResult<()> {
+    println!("SET 3 CHALLENGE 18");
+    println!("{}", solve_challenge18()?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge18() -> MyResult<()> {
+    let plaintext = solve_challenge18()?;
+    assert_eq!(
+        plaintext,
+        "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+    );
+
+    let mode = Mode::CTR(CtrLayout::CRYPTOPALS);
+    let ciphertext = plaintext
+        .as_bytes()
+        .encrypt(mode, b"YELLOW SUBMARINE", Some(&[0; 8]))?;
+    assert_eq!(ciphertext, from_base64(CHALLENGE_18_INPUT)?);
+    Ok(())
+}
+
+fn encrypt_fixed_nonce(plaintexts: &[Vec<u8>], key: &[u8]) -> MyResult<Vec<Vec<u8>>> {
+    let nonce = [0; 8];
+    let mode = Mode::CTR(CtrLayout::CRYPTOPALS);
+    Ok(plaintexts
+        .iter()
+        .map(|plaintext| plaintext.encrypt(mode, key, Some(&nonce)))
+        .collect::<Result<_, _>>()?)
+}
+
+/**
+ * Fraction of the plaintext bytes that decrypt correctly with the recovered
+ * keystream, both up to the shortest plaintext and overall. The key comes from a
+ * seeded generator so the result doesn't vary between runs.
+ */
+#[cfg(test)]
+fn fixed_nonce_accuracy(plaintexts: &[Vec<u8>], seed: u32) -> MyResult<(f32, f32)> {
+    use rand::RngCore;
+
+    let mut key = [0; BLOCK_SIZE];
+    Mt19937::new(seed).fill_bytes(&mut key);
+    let ciphertexts = encrypt_fixed_nonce(plaintexts, &key)?;
+    let recovered = recover_keystream(&ciphertexts).ok_or("could not recover keystream")?;
+    assert!(recovered
+        .confidence
+        .iter()
+        .all(|confidence| confidence.samples > 0));
+
+    let min_len = plaintexts.iter().map(Vec::len).min().unwrap();
+    let accuracy = |len: fn(&[u8], usize) -> usize| {
+        let (mut correct, mut total) = (0, 0);
+        for (plaintext, ciphertext) in plaintexts.iter().zip(&ciphertexts) {
+            let len = len(plaintext, min_len);
+            let decrypted = recovered.decrypt(&ciphertext[..len]);
+            correct += decrypted
+                .iter()
+                .zip(&plaintext[..len])
+                .filter(|(l, r)| l == r)
+                .count();
+            total += len;
+        }
+        correct as f32 / total as f32
+    };
+    Ok((
+        accuracy(|_, min_len| min_len),
+        accuracy(|plaintext, _| plaintext.len()),
+    ))
+}
+
+pub fn challenge19() -> MyResult<()> {
+    println!("SET 3 CHALLENGE 19");
+    let plaintexts = base64::from_file_lines(Path::new("data/challenge19.txt"))?;
+    let ciphertexts = encrypt_fixed_nonce(&plaintexts, &random_bytes(BLOCK_SIZE))?;
+    let recovered = recover_keystream(&ciphertexts).ok_or("could not recover keystream")?;
+    for ciphertext in &ciphertexts {
+        println!(
+            "{}",
+            String::from_utf8_lossy(&recovered.decrypt(ciphertext))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge19() -> MyResult<()> {
+    let plaintexts = base64::from_file_lines(Path::new("data/challenge19.txt"))?;
+    let (truncated, overall) = fixed_nonce_accuracy(&plaintexts, 19)?;
+    assert!(truncated > 0.95);
+    assert!(overall > 0.8);
+    Ok(())
+}
+
+// The challenge 20 data isn't in the repo, so the lyrics from challenge 7 stand in
+// for it: many lines of similar English text encrypted under the same nonce.
+fn challenge20_plaintexts() -> MyResult<Vec<Vec<u8>>> {
+    Ok(
+        read_file_to_string(Path::new("data/challenge7.actual.txt"))?
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.as_bytes().to_vec())
+            .collect(),
+    )
+}
+
+pub fn challenge20() -> MyResult<()> {
+    println!("SET 3 CHALLENGE 20");
+    let plaintexts = challenge20_plaintexts()?;
+    let ciphertexts = encrypt_fixed_nonce(&plaintexts, &random_bytes(BLOCK_SIZE))?;
+    let recovered = recover_keystream(&ciphertexts).ok_or("could not recover keystream")?;
+    for ciphertext in &ciphertexts {
+        println!(
+            "{}",
+            String::from_utf8_lossy(&recovered.decrypt(ciphertext))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge20() -> MyResult<()> {
+    let (truncated, overall) = fixed_nonce_accuracy(&challenge20_plaintexts()?, 20)?;
+    assert!(truncated > 0.9);
+    assert!(overall > 0.9);
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs the challenge's 20.txt saved as data/challenge20.txt"]
+fn test_challenge20_data() -> MyResult<()> {
+    let plaintexts = base64::from_file_lines(Path::new("data/challenge20.txt"))?;
+    let (truncated, overall) = fixed_nonce_accuracy(&plaintexts, 20)?;
+    assert!(truncated > 0.95);
+    assert!(overall > 0.8);
+    Ok(())
+}
+
+pub fn challenge21() {
+    println!("SET 3 CHALLENGE 21");
+    let mut mt = Mt19937::new(5489);
+    let outputs: Vec<u32> = (0..5).map(|_| mt.next_u32()).collect();
+    println!("first outputs for seed 5489: {:?}", outputs);
+}
+
+#[test]
+fn test_challenge21() {
+    let mut mt = Mt19937::new(5489);
+    let outputs: Vec<u32> = (0..5).map(|_| mt.next_u32()).collect();
+    assert_eq!(
+        outputs,
+        [3499211612, 581869302, 3890346734, 3586334585, 545404204]
+    );
+}
+
+fn unix_timestamp() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u32
+}
+
+// Instead of actually sleeping, pretend the generator was seeded 40-1000 seconds ago
+// and that its first output is returned another 40-1000 seconds later.
+fn solve_challenge22() -> (u32, Option<u32>) {
+    let mut rng = rand::thread_rng();
+    let now = unix_timestamp();
+    let seed = now - rng.gen_range(40..=1000);
+    let output = Mt19937::new(seed).next_u32();
+    let returned_at = seed + rng.gen_range(40..=1000);
+    (seed, crack_timestamp_seed(output, returned_at, 2000))
+}
+
+pub fn challenge22() {
+    println!("SET 3 CHALLENGE 22");
+    let (seed, cracked) = solve_challenge22();
+    println!("seeded with {}, cracked {:?}", seed, cracked);
+}
+
+#[test]
+fn test_challenge22() {
+    let (seed, cracked) = solve_challenge22();
+    assert_eq!(cracked, Some(seed));
+}
+
+pub fn challenge23() {
+    println!("SET 3 CHALLENGE 23");
+    let mut mt = Mt19937::new(rand::random());
+    let mut outputs = [0u32; N];
+    outputs
+        .iter_mut()
+        .for_each(|output| *output = mt.next_u32());
+    let mut clone = clone_from_outputs(&outputs);
+    let matches = (0..1000).all(|_| mt.next_u32() == clone.next_u32());
+    println!("clone predicts the next 1000 outputs: {}", matches);
+}
+
+#[test]
+fn test_challenge23() {
+    let mut mt = Mt19937::new(rand::random());
+    let mut outputs = [0u32; N];
+    outputs
+        .iter_mut()
+        .for_each(|output| *output = mt.next_u32());
+    let mut clone = clone_from_outputs(&outputs);
+    for _ in 0..1000 {
+        assert_eq!(mt.next_u32(), clone.next_u32());
+    }
+}
+
+// a random number of random characters, then 14 'A's
+fn solve_challenge24() -> MyResult<(u16, Option<u16>)> {
+    let mut rng = rand::thread_rng();
+    let key: u16 = rng.gen();
+    let known = [b'A'; 14];
+    let mut plaintext = random_bytes(rng.gen_range(0..=32));
+    plaintext.extend_from_slice(&known);
+    let ciphertext = plaintext.encrypt(Mode::MT19937, &key.to_be_bytes(), None)?;
+    Ok((key, recover_stream_key(&ciphertext, &known)))
+}
+
+pub fn challenge24() -> MyResult<()> {
+    println!("SET 3 CHALLENGE 24");
+    let (key, recovered) = solve_challenge24()?;
+    println!("key {}, recovered {:?}", key, recovered);
+
+    let now = unix_timestamp();
+    let token = password_reset_token(now);
+    println!(
+        "token from the current time is an MT19937 token: {}",
+        is_mt_token(&token, now - 3600..=now)
+    );
+    println!(
+        "random token is an MT19937 token: {}",
+        is_mt_token(&random_bytes(token.len()), now - 3600..=now)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_challenge24() -> MyResult<()> {
+    let (key, recovered) = solve_challenge24()?;
+    assert_eq!(recovered, Some(key));
+
+    let now = unix_timestamp();
+    let token = password_reset_token(now - 600);
+    assert!(is_mt_token(&token, now - 3600..=now));
+    assert!(!is_mt_token(&random_bytes(token.len()), now - 3600..=now));
+    assert!(!is_mt_token(&[], now - 3600..=now));
+    Ok(())
+}
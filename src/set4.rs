@@ -0,0 +1,224 @@
+use crate::{
+    aes::{CtrLayout, Decrypt, Mode},
+    attacks::{
+        bitflipping::{ctr_forge_admin, CommentOracle},
+        cbc_key_as_iv::{recover_key, KeyAsIvOracle},
+        ctr_edit::{recover_plaintext, EditOracle},
+        length_extension::{forge_admin, MacService, MESSAGE},
+        timing_leak::{check_signature, recover_mac, TimingServer},
+    },
+    hash::{Md4, MerkleDamgard, Sha1},
+    serializers::{base64, Serialize},
+    MyResult,
+};
+use std::{path::Path, time::Duration};
+
+// the challenge 7 plaintext, re-encrypted under CTR with a random key
+fn challenge25_oracle() -> MyResult<(Vec<u8>, EditOracle)> {
+    let ciphertext = base64::from_file(Path::new("data/challenge7.txt"))?;
+    let plaintext = ciphertext.decrypt(Mode::ECB, b"YELLOW SUBMARINE", None)?;
+    let oracle = EditOracle::new(&plaintext);
+    Ok((plaintext, oracle))
+}
+
+pub fn challenge25() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 25");
+    let (_, oracle) = challenge25_oracle()?;
+    let recovered = recover_plaintext(oracle.ciphertext(), |ciphertext, offset, newtext| {
+        oracle.edit(ciphertext, offset, newtext)
+    });
+    println!("{}", String::from_utf8(recovered)?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge25() -> MyResult<()> {
+    let (plaintext, oracle) = challenge25_oracle()?;
+    let recovered = recover_plaintext(oracle.ciphertext(), |ciphertext, offset, newtext| {
+        oracle.edit(ciphertext, offset, newtext)
+    });
+    assert_eq!(recovered, plaintext);
+    Ok(())
+}
+
+pub fn challenge26() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 26");
+    let oracle = CommentOracle::new_ctr(CtrLayout::CRYPTOPALS);
+    let forged = ctr_forge_admin(|userdata| oracle.encrypt(userdata));
+    println!("{}", String::from_utf8_lossy(&oracle.decrypt(&forged)?));
+    println!("is admin: {}", oracle.is_admin(&forged)?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge26() -> MyResult<()> {
+    let oracle = CommentOracle::new_ctr(CtrLayout::CRYPTOPALS);
+    assert!(!oracle.is_admin(&oracle.encrypt(b";admin=true;"))?);
+    let forged = ctr_forge_admin(|userdata| oracle.encrypt(userdata));
+    assert!(oracle.is_admin(&forged)?);
+    Ok(())
+}
+
+pub fn challenge27() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 27");
+    let oracle = KeyAsIvOracle::new();
+    let ciphertext = oracle
+        .encrypt(b"comment1=cooking%20MCs;userdata=;comment2=%20like%20a%20pound%20of%20bacon");
+    let key = recover_key(&ciphertext, |ciphertext| oracle.decrypt(ciphertext))?;
+    oracle.verify(&key)?;
+    println!("recovered key: {:?}", key);
+    Ok(())
+}
+
+#[test]
+fn test_challenge27() -> MyResult<()> {
+    let oracle = KeyAsIvOracle::new();
+    let ciphertext = oracle.encrypt(&[b'A'; 3 * 16]);
+    let key = recover_key(&ciphertext, |ciphertext| oracle.decrypt(ciphertext))?;
+    oracle.verify(&key)?;
+    Ok(())
+}
+
+pub fn challenge28() {
+    println!("SET 4 CHALLENGE 28");
+    let key = b"YELLOW SUBMARINE";
+    let mac = Sha1::secret_prefix_mac(key, MESSAGE);
+    println!("mac: {}", mac.to_hex());
+    let mut tampered = MESSAGE.to_vec();
+    tampered[0] ^= 1;
+    println!(
+        "tampered message has the same mac: {}",
+        Sha1::secret_prefix_mac(key, &tampered) == mac
+    );
+    println!(
+        "mac without the key has the same mac: {}",
+        Sha1::secret_prefix_mac(b"", MESSAGE) == mac
+    );
+}
+
+#[test]
+fn test_challenge28() {
+    let key = b"YELLOW SUBMARINE";
+    let mac = Sha1::secret_prefix_mac(key, MESSAGE);
+    let mut tampered = MESSAGE.to_vec();
+    tampered[0] ^= 1;
+    assert_ne!(Sha1::secret_prefix_mac(key, &tampered), mac);
+    assert_ne!(Sha1::secret_prefix_mac(b"", MESSAGE), mac);
+    assert_eq!(Sha1::secret_prefix_mac(key, MESSAGE), mac);
+}
+
+pub fn challenge29() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 29");
+    let service = MacService::<Sha1>::new();
+    let mac = service.sign(MESSAGE);
+    let (forged, forged_mac) =
+        forge_admin::<Sha1>(MESSAGE, &mac, |message, mac| service.verify(message, mac))
+            .ok_or("could not forge a mac")?;
+    println!("{}", String::from_utf8_lossy(&forged));
+    println!("is admin: {}", service.is_admin(&forged, &forged_mac));
+    Ok(())
+}
+
+#[test]
+fn test_challenge29() -> MyResult<()> {
+    let service = MacService::<Sha1>::new();
+    let mac = service.sign(MESSAGE);
+    assert!(!service.is_admin(MESSAGE, &mac));
+    let (forged, forged_mac) =
+        forge_admin::<Sha1>(MESSAGE, &mac, |message, mac| service.verify(message, mac))
+            .ok_or("could not forge a mac")?;
+    assert!(service.is_admin(&forged, &forged_mac));
+    Ok(())
+}
+
+pub fn challenge30() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 30");
+    let service = MacService::<Md4>::new();
+    let mac = service.sign(MESSAGE);
+    let (forged, forged_mac) =
+        forge_admin::<Md4>(MESSAGE, &mac, |message, mac| service.verify(message, mac))
+            .ok_or("could not forge a mac")?;
+    println!("{}", String::from_utf8_lossy(&forged));
+    println!("is admin: {}", service.is_admin(&forged, &forged_mac));
+    Ok(())
+}
+
+#[test]
+fn test_challenge30() -> MyResult<()> {
+    let service = MacService::<Md4>::new();
+    let mac = service.sign(MESSAGE);
+    assert!(!service.is_admin(MESSAGE, &mac));
+    let (forged, forged_mac) =
+        forge_admin::<Md4>(MESSAGE, &mac, |message, mac| service.verify(message, mac))
+            .ok_or("could not forge a mac")?;
+    assert!(service.is_admin(&forged, &forged_mac));
+    Ok(())
+}
+
+// The demos truncate the MAC so that they finish in seconds rather than an hour: each
+// extra byte costs hundreds more requests, each slower than the last. The ignored tests
+// recover the full MAC.
+const TIMING_DEMO_MAC_LEN: usize = 2;
+
+// the recovered MAC, and whether the server then accepts it
+fn solve_timing_leak(delay: Duration, samples: usize, mac_len: usize) -> MyResult<(Vec<u8>, bool)> {
+    let server = TimingServer::start(delay, mac_len)?;
+    let mac = recover_mac(mac_len, samples, |signature| {
+        check_signature(server.addr(), "foo", signature)
+    })?;
+    let accepted = check_signature(server.addr(), "foo", &mac)?;
+    Ok((mac, accepted))
+}
+
+pub fn challenge31() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 31");
+    let (mac, _) = solve_timing_leak(Duration::from_millis(50), 1, TIMING_DEMO_MAC_LEN)?;
+    println!(
+        "recovered the first {} bytes of the mac for foo: {}",
+        TIMING_DEMO_MAC_LEN,
+        mac.to_hex()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_timing_leak_truncated() -> MyResult<()> {
+    // a short delay and mac keep this to a few seconds
+    let (mac, accepted) = solve_timing_leak(Duration::from_millis(2), 1, TIMING_DEMO_MAC_LEN)?;
+    assert_eq!(mac.len(), TIMING_DEMO_MAC_LEN);
+    assert!(accepted);
+    Ok(())
+}
+
+#[test]
+#[ignore = "takes over an hour: the full 20-byte mac at 50ms per byte"]
+fn test_challenge31() -> MyResult<()> {
+    use crate::hash::sha1::DIGEST_SIZE;
+
+    let (mac, accepted) = solve_timing_leak(Duration::from_millis(50), 1, DIGEST_SIZE)?;
+    assert_eq!(mac.len(), DIGEST_SIZE);
+    assert!(accepted);
+    Ok(())
+}
+
+pub fn challenge32() -> MyResult<()> {
+    println!("SET 4 CHALLENGE 32");
+    let (mac, _) = solve_timing_leak(Duration::from_millis(5), 3, TIMING_DEMO_MAC_LEN)?;
+    println!(
+        "recovered the first {} bytes of the mac for foo: {}",
+        TIMING_DEMO_MAC_LEN,
+        mac.to_hex()
+    );
+    Ok(())
+}
+
+#[test]
+#[ignore = "takes about half an hour: the full 20-byte mac at 5ms per byte"]
+fn test_challenge32() -> MyResult<()> {
+    use crate::hash::sha1::DIGEST_SIZE;
+
+    let (mac, accepted) = solve_timing_leak(Duration::from_millis(5), 3, DIGEST_SIZE)?;
+    assert_eq!(mac.len(), DIGEST_SIZE);
+    assert!(accepted);
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use crate::{
+    aes::random_bytes,
+    attacks::{
+        dh_mitm::{
+            run_exchange, Alice, Bob, MaliciousG, MaliciousGroup, NoMitm, ParameterInjection,
+        },
+        rsa_broadcast::broadcast_attack,
+        srp::{login_with_zero_key, SimpleSrpMitm, COMMON_PASSWORDS},
+    },
+    bigint::BigUint,
+    dh::{DhParams, Keypair},
+    rsa::{self, invmod},
+    serializers::Serialize,
+    srp::{Client, Server},
+    MyResult,
+};
+use rand::seq::SliceRandom;
+
+const MESSAGE: &[u8] = b"Ice Ice Baby, too cold";
+
+fn small_group() -> DhParams {
+    DhParams {
+        p: BigUint::from(37u32),
+        g: BigUint::from(5u32),
+    }
+}
+
+// hex with a 0x prefix, cut to the first 8 digits when it's longer
+fn abbreviate(n: &BigUint) -> String {
+    let mut hex = n.to_hex();
+    if hex.len() > 8 {
+        hex.replace_range(8.., "...");
+    }
+    format!("0x{}", hex)
+}
+
+// one exchange in the group, with every value in hex
+fn describe_exchange(params: &DhParams) -> String {
+    let a = Keypair::generate(params);
+    let b = Keypair::generate(params);
+    let s = a.shared_secret(params, &b.public);
+    format!(
+        "p = {}, shared secret {} (agree: {})",
+        abbreviate(&params.p),
+        abbreviate(&s),
+        s == b.shared_secret(params, &a.public)
+    )
+}
+
+pub fn challenge33() {
+    println!("SET 5 CHALLENGE 33");
+    for params in [small_group(), DhParams::nist()] {
+        println!("{}", describe_exchange(&params));
+    }
+}
+
+#[test]
+fn test_challenge33() {
+    let params = small_group();
+    for _ in 0..20 {
+        let a = Keypair::generate(&params);
+        let b = Keypair::generate(&params);
+        assert_eq!(
+            a.shared_secret(&params, &b.public),
+            b.shared_secret(&params, &a.public)
+        );
+    }
+
+    let small = describe_exchange(&params);
+    assert!(small.starts_with("p = 0x25, shared secret 0x"), "{}", small);
+    assert!(small.ends_with("(agree: true)"), "{}", small);
+    let nist = describe_exchange(&DhParams::nist());
+    assert!(
+        nist.starts_with("p = 0xffffffff..., shared secret 0x"),
+        "{}",
+        nist
+    );
+    assert!(nist.ends_with("(agree: true)"), "{}", nist);
+}
+
+pub fn challenge34() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 34");
+    let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+    run_exchange(&mut alice, &mut bob, &mut NoMitm, false)?;
+    println!(
+        "without mitm, alice got back: {}",
+        String::from_utf8_lossy(alice.echo().unwrap_or_default())
+    );
+
+    let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+    let mut mitm = ParameterInjection::new();
+    run_exchange(&mut alice, &mut bob, &mut mitm, false)?;
+    for plaintext in &mitm.recovered {
+        println!("mitm read: {}", String::from_utf8_lossy(plaintext));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge34() -> MyResult<()> {
+    let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+    run_exchange(&mut alice, &mut bob, &mut NoMitm, false)?;
+    assert_eq!(alice.echo(), Some(MESSAGE));
+
+    let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+    let mut mitm = ParameterInjection::new();
+    run_exchange(&mut alice, &mut bob, &mut mitm, false)?;
+    assert_eq!(alice.echo(), Some(MESSAGE));
+    assert_eq!(bob.received(), [MESSAGE]);
+    assert_eq!(mitm.recovered, [MESSAGE, MESSAGE]);
+    Ok(())
+}
+
+const MALICIOUS_GS: [MaliciousG; 3] = [MaliciousG::One, MaliciousG::P, MaliciousG::PMinusOne];
+
+pub fn challenge35() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 35");
+    for g in MALICIOUS_GS {
+        let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+        let mut mitm = MaliciousGroup::new(g);
+        run_exchange(&mut alice, &mut bob, &mut mitm, true)?;
+        for plaintext in &mitm.recovered {
+            println!(
+                "g = {:?}, mitm read: {}",
+                g,
+                String::from_utf8_lossy(plaintext)
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge35() -> MyResult<()> {
+    for g in MALICIOUS_GS {
+        let (mut alice, mut bob) = (Alice::new(DhParams::nist(), MESSAGE), Bob::new());
+        let mut mitm = MaliciousGroup::new(g);
+        run_exchange(&mut alice, &mut bob, &mut mitm, true)?;
+        assert_eq!(alice.echo(), Some(MESSAGE));
+        assert_eq!(mitm.recovered, [MESSAGE, MESSAGE]);
+    }
+    Ok(())
+}
+
+const EMAIL: &str = "alice@example.com";
+
+pub fn challenge36() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 36");
+    let mut server = Server::new();
+    server.register(EMAIL, "correct horse battery staple");
+    let ok = Client::new(EMAIL, "correct horse battery staple").login(&mut server)?;
+    let wrong = Client::new(EMAIL, "hunter2").login(&mut server)?;
+    println!("right password: {}, wrong password: {}", ok, wrong);
+    Ok(())
+}
+
+#[test]
+fn test_challenge36() -> MyResult<()> {
+    let mut server = Server::new();
+    server.register(EMAIL, "correct horse battery staple");
+    assert!(Client::new(EMAIL, "correct horse battery staple").login(&mut server)?);
+    assert!(!Client::new(EMAIL, "hunter2").login(&mut server)?);
+    Ok(())
+}
+
+fn zero_keys() -> [BigUint; 3] {
+    let n = DhParams::nist().p;
+    [BigUint::zero(), n.clone(), &n * &BigUint::from(2u32)]
+}
+
+pub fn challenge37() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 37");
+    let mut server = Server::new();
+    server.register(EMAIL, &random_bytes(16).to_hex());
+    for (a_public, name) in zero_keys().iter().zip(["0", "N", "2N"]) {
+        println!(
+            "logged in with A = {}: {}",
+            name,
+            login_with_zero_key(&mut server, EMAIL, a_public)?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge37() -> MyResult<()> {
+    let mut server = Server::new();
+    server.register(EMAIL, &random_bytes(16).to_hex());
+    for a_public in zero_keys() {
+        assert!(login_with_zero_key(&mut server, EMAIL, &a_public)?);
+    }
+    assert!(!login_with_zero_key(&mut server, EMAIL, &BigUint::one())?);
+    Ok(())
+}
+
+fn solve_challenge38() -> MyResult<(&'static str, Option<&'static str>)> {
+    let password = COMMON_PASSWORDS
+        .choose(&mut rand::thread_rng())
+        .ok_or("no passwords")?;
+    let mut mitm = SimpleSrpMitm::new();
+    Client::new(EMAIL, password).simple_login(&mut mitm)?;
+    Ok((password, mitm.crack(COMMON_PASSWORDS)))
+}
+
+pub fn challenge38() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 38");
+    let (password, cracked) = solve_challenge38()?;
+    println!("password {}, cracked {:?}", password, cracked);
+    Ok(())
+}
+
+#[test]
+fn test_challenge38() -> MyResult<()> {
+    let (password, cracked) = solve_challenge38()?;
+    assert_eq!(cracked, Some(password));
+    Ok(())
+}
+
+const RSA_BITS: usize = 1024;
+
+pub fn challenge39() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 39");
+    println!(
+        "invmod(17, 3120) = {:?}",
+        invmod(&BigUint::from(17u32), &BigUint::from(3120u32))
+    );
+    let keypair = rsa::Keypair::generate(RSA_BITS, 3, &mut rand::thread_rng());
+    let ciphertext = keypair.public.encrypt(MESSAGE)?;
+    println!(
+        "decrypted: {}",
+        String::from_utf8_lossy(&keypair.private.decrypt(&ciphertext)?)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_challenge39() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    assert_eq!(
+        invmod(&BigUint::from(17u32), &BigUint::from(3120u32)),
+        Some(BigUint::from(2753u32))
+    );
+    assert_eq!(invmod(&BigUint::from(6u32), &BigUint::from(9u32)), None);
+
+    let keypair = rsa::Keypair::generate(512, 3, &mut Mt19937::new(39));
+    let m = BigUint::from(42u32);
+    let c = keypair.public.encrypt_int(&m);
+    assert_eq!(c, BigUint::from(42u32 * 42 * 42));
+    assert_eq!(keypair.private.decrypt_int(&c), m);
+
+    let ciphertext = keypair.public.encrypt(MESSAGE)?;
+    assert_eq!(keypair.private.decrypt(&ciphertext)?, MESSAGE);
+    Ok(())
+}
+
+// the same message encrypted to three freshly generated e=3 keys
+fn solve_challenge40(bits: usize, rng: &mut impl rand::Rng) -> MyResult<Vec<u8>> {
+    let mut encrypt = || -> MyResult<_> {
+        let public = rsa::Keypair::generate(bits, 3, rng).public;
+        let c = BigUint::from_bytes_be(&public.encrypt(MESSAGE)?);
+        Ok((c, public))
+    };
+    broadcast_attack(&[encrypt()?, encrypt()?, encrypt()?])
+}
+
+pub fn challenge40() -> MyResult<()> {
+    println!("SET 5 CHALLENGE 40");
+    let recovered = solve_challenge40(RSA_BITS, &mut rand::thread_rng())?;
+    println!("recovered: {}", String::from_utf8_lossy(&recovered));
+    Ok(())
+}
+
+#[test]
+fn test_challenge40() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    assert_eq!(solve_challenge40(512, &mut Mt19937::new(40))?, MESSAGE);
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use crate::{
+    attacks::{
+        dsa_nonce::{
+            find_repeated_nonces, parse_signed_messages, recover_key_from_repeated_nonce,
+            recover_key_from_small_nonce,
+        },
+        dsa_params::magic_signature,
+        rsa_parity::{self, ParityOracle, Progress},
+        rsa_signature_forgery::{forge_signature, verify_sloppy},
+        rsa_unpadded::{recover_plaintext, UnpaddedRsaService},
+    },
+    bigint::BigUint,
+    dsa::{self, verify, verify_unchecked, DsaParams, Signature},
+    hash::sha1,
+    rsa,
+    serializers::{base64::from_base64, Serialize},
+    utils::read_file_to_string,
+    MyResult,
+};
+use std::path::Path;
+
+const RSA_BITS: usize = 1024;
+
+const MESSAGE: &[u8] = b"{ time: 1356304276, social: '555-55-5555' }";
+
+// a client's message goes through the service once, then the attacker replays it
+fn solve_challenge41(bits: usize, rng: &mut impl rand::Rng) -> MyResult<(Vec<u8>, bool)> {
+    let mut service = UnpaddedRsaService::new(rsa::Keypair::generate(bits, 65537, rng));
+    let public = service.public_key().clone();
+    let ciphertext = public.encrypt(MESSAGE)?;
+    service.decrypt(&ciphertext)?;
+    let refused = service.decrypt(&ciphertext).is_err();
+    let recovered = recover_plaintext(&public, &ciphertext, rng, |ciphertext| {
+        service.decrypt(ciphertext)
+    })?;
+    Ok((recovered, refused))
+}
+
+pub fn challenge41() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 41");
+    let (recovered, refused) = solve_challenge41(RSA_BITS, &mut rand::thread_rng())?;
+    println!("service refused the replayed ciphertext: {}", refused);
+    println!("recovered: {}", String::from_utf8_lossy(&recovered));
+    Ok(())
+}
+
+#[test]
+fn test_challenge41() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    let (recovered, refused) = solve_challenge41(512, &mut Mt19937::new(41))?;
+    assert!(refused);
+    assert_eq!(recovered, MESSAGE);
+    Ok(())
+}
+
+const CHALLENGE_42_MESSAGE: &[u8] = b"hi mom";
+
+// the strict and sloppy verdicts on a forged signature
+fn solve_challenge42(rng: &mut impl rand::Rng) -> MyResult<(bool, bool)> {
+    let public = rsa::Keypair::generate(RSA_BITS, 3, rng).public;
+    let forged = forge_signature(&public, CHALLENGE_42_MESSAGE)?;
+    Ok((
+        public.verify(CHALLENGE_42_MESSAGE, &forged),
+        verify_sloppy(&public, CHALLENGE_42_MESSAGE, &forged),
+    ))
+}
+
+pub fn challenge42() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 42");
+    let (strict, sloppy) = solve_challenge42(&mut rand::thread_rng())?;
+    println!(
+        "forged signature accepted by the strict verifier: {}",
+        strict
+    );
+    println!(
+        "forged signature accepted by the sloppy verifier: {}",
+        sloppy
+    );
+    Ok(())
+}
+
+#[test]
+fn test_challenge42() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    assert_eq!(solve_challenge42(&mut Mt19937::new(42))?, (false, true));
+    Ok(())
+}
+
+const CHALLENGE_43_Y_HEX: &str = "
+    84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4bdebf2955b4736012f21a08084056b19bcd7fee56048e004e44984e2f411788efdc837a0d2e5abb7b555039fd243ac01f0fb2ed1dec568280ce678e931868d23eb095fde9d3779191b8c0299d6e07bbb283e6633451e535c45513b2d33c99ea17";
+const CHALLENGE_43_MESSAGE: &[u8] =
+    b"For those that envy a MC it can be hazardous to your health\n\
+So be friendly, a matter of life and death, just like a etch-a-sketch\n";
+const CHALLENGE_43_R: &str = "548099063082341131477253921760299949438196259240";
+const CHALLENGE_43_S: &str = "857042759984254168557880549501802188789837994940";
+
+// the SHA-1 of the recovered private key's hex
+fn solve_challenge43() -> MyResult<String> {
+    let params = DsaParams::cryptopals();
+    let y = BigUint::from_hex(CHALLENGE_43_Y_HEX)?;
+    let signature = Signature {
+        r: BigUint::from_dec(CHALLENGE_43_R)?,
+        s: BigUint::from_dec(CHALLENGE_43_S)?,
+    };
+    let hash = dsa::hash_message(CHALLENGE_43_MESSAGE);
+    let x = recover_key_from_small_nonce(&params, &y, &hash, &signature, 1 << 16)
+        .ok_or("no 16-bit nonce matches the signature")?;
+    Ok(sha1(x.to_hex().as_bytes()).to_hex())
+}
+
+pub fn challenge43() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 43");
+    println!("private key sha1: {}", solve_challenge43()?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge43() -> MyResult<()> {
+    assert_eq!(
+        dsa::hash_message(CHALLENGE_43_MESSAGE).to_hex(),
+        "d2d0714f014a9784047eaeccf956520045c45265"
+    );
+    assert_eq!(
+        solve_challenge43()?,
+        "0954edd5e0afe5542a4adf012611a91912a3ec16"
+    );
+    Ok(())
+}
+
+// the challenge's public key. data/challenge44.txt holds nine of the eleven entries of
+// its 44.txt, including all three pairs of messages that were signed with the same nonce
+const CHALLENGE_44_Y_HEX: &str = "
+    2d026f4bf30195ede3a088da85e398ef869611d0f68f0713d51c9c1a3a26c95105d915e2d8cdf26d056b86b8a7b85519b1c23cc3ecdc6062650462e3063bd179c2a6581519f674a61f1d89a1fff27171ebc1b93d4dc57bceb7ae2430f98a6a4d83d8279ee65d71c1203d2c96d65ebbf7cce9d32971c3de5084cce04a2e147821";
+
+/**
+ * Finds the private key for `public` from a list of signed messages in the challenge 44
+ * format, using the first pair with a repeated nonce that gives a key matching it
+ */
+fn recover_challenge44_key(params: &DsaParams, public: &BigUint, text: &str) -> MyResult<BigUint> {
+    let messages = parse_signed_messages(text)?;
+    find_repeated_nonces(&messages)
+        .into_iter()
+        .filter_map(|(i, j)| recover_key_from_repeated_nonce(params, &messages[i], &messages[j]))
+        .find(|x| &params.public_key(x) == public)
+        .ok_or_else(|| "no pair of messages gives the private key".into())
+}
+
+// the sha1 of the recovered private key's hex, which the challenge gives to check it
+fn solve_challenge44() -> MyResult<String> {
+    let params = DsaParams::cryptopals();
+    let y = BigUint::from_hex(CHALLENGE_44_Y_HEX)?;
+    let text = read_file_to_string(Path::new("data/challenge44.txt"))?;
+    let x = recover_challenge44_key(&params, &y, &text)?;
+    Ok(sha1(x.to_hex().as_bytes()).to_hex())
+}
+
+pub fn challenge44() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 44");
+    println!("private key sha1: {}", solve_challenge44()?);
+    Ok(())
+}
+
+#[test]
+fn test_challenge44() -> MyResult<()> {
+    let params = DsaParams::cryptopals();
+    let y = BigUint::from_hex(CHALLENGE_44_Y_HEX)?;
+    let messages = parse_signed_messages(&read_file_to_string(Path::new("data/challenge44.txt"))?)?;
+    assert_eq!(messages.len(), 9);
+    for message in &messages {
+        assert_eq!(message.m, dsa::hash_message(message.msg.as_bytes()));
+        assert!(verify(
+            &params,
+            &y,
+            message.msg.as_bytes(),
+            &message.signature
+        ));
+    }
+    assert_eq!(find_repeated_nonces(&messages), [(0, 6), (1, 7), (2, 8)]);
+    assert_eq!(
+        solve_challenge44()?,
+        "ca8f6f7c66fa362d40760d135b763eb8527d3d52"
+    );
+    Ok(())
+}
+
+const CHALLENGE_45_MESSAGES: [&[u8]; 2] = [b"Hello, world", b"Goodbye, world"];
+
+// whether a magic signature made under a tampered g verifies for every message, with
+// and without the range checks
+fn solve_challenge45(g: BigUint, rng: &mut impl rand::Rng) -> MyResult<(bool, bool)> {
+    let params = DsaParams::cryptopals();
+    let public = dsa::Keypair::generate(&params, rng).public;
+    let tampered = DsaParams { g, ..params };
+    let signature = magic_signature(&tampered, &public, rng).ok_or("g is not 0 or 1 mod p")?;
+    let all_verify = |verify: fn(&DsaParams, &BigUint, &[u8], &Signature) -> bool| {
+        CHALLENGE_45_MESSAGES
+            .iter()
+            .all(|message| verify(&tampered, &public, message, &signature))
+    };
+    Ok((all_verify(verify), all_verify(verify_unchecked)))
+}
+
+pub fn challenge45() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 45");
+    let p_plus_1 = &DsaParams::cryptopals().p + &BigUint::one();
+    for (name, g) in [("0", BigUint::zero()), ("p + 1", p_plus_1)] {
+        let (strict, unchecked) = solve_challenge45(g, &mut rand::thread_rng())?;
+        println!(
+            "g = {}: magic signature verifies with range checks: {}, without: {}",
+            name, strict, unchecked
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_challenge45() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    // r = 0 is caught by the range check, but r = y^z mod p mod q isn't
+    let mut rng = Mt19937::new(45);
+    assert_eq!(solve_challenge45(BigUint::zero(), &mut rng)?, (false, true));
+    let p_plus_1 = &DsaParams::cryptopals().p + &BigUint::one();
+    assert_eq!(solve_challenge45(p_plus_1, &mut rng)?, (true, true));
+    Ok(())
+}
+
+const CHALLENGE_46_INPUT: &str =
+    "VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==";
+
+// the challenge's message encrypted under a fresh key, then recovered through the oracle
+fn solve_challenge46(
+    bits: usize,
+    rng: &mut impl rand::Rng,
+    progress: Progress,
+) -> MyResult<Vec<u8>> {
+    let oracle = ParityOracle::new(rsa::Keypair::generate(bits, 65537, rng));
+    let ciphertext = oracle
+        .public_key()
+        .encrypt(&from_base64(CHALLENGE_46_INPUT)?)?;
+    Ok(rsa_parity::recover_plaintext(
+        oracle.public_key(),
+        &ciphertext,
+        |ciphertext| oracle.is_even(ciphertext),
+        Some(progress),
+    ))
+}
+
+pub fn challenge46() -> MyResult<()> {
+    println!("SET 6 CHALLENGE 46");
+    let mut queries = 0;
+    let recovered = solve_challenge46(RSA_BITS, &mut rand::thread_rng(), &mut |upper| {
+        queries += 1;
+        // every bit would be a lot of output, so show every 64th
+        if queries % 64 == 0 {
+            println!("{:?}", String::from_utf8_lossy(upper));
+        }
+    })?;
+    println!(
+        "recovered after {} queries: {}",
+        queries,
+        String::from_utf8_lossy(&recovered)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_challenge46() -> MyResult<()> {
+    use crate::mt19937::Mt19937;
+
+    // the 67-byte message is a 535-bit number, too big for a 512-bit modulus, and every
+    // extra bit of key costs another oracle query, so use the smallest whole-word size
+    // that fits it
+    let bits = 576;
+    let mut bounds = Vec::new();
+    let recovered = solve_challenge46(bits, &mut Mt19937::new(46), &mut |upper| {
+        bounds.push(upper.to_vec())
+    })?;
+    assert_eq!(recovered, from_base64(CHALLENGE_46_INPUT)?);
+    assert_eq!(bounds.len(), bits);
+    assert_eq!(bounds.last(), Some(&recovered));
+    Ok(())
+}
@@ -0,0 +1,293 @@
+use crate::{
+    aes::random_bytes,
+    bigint::BigUint,
+    dh::DhParams,
+    hash::{hmac_sha256, sha256},
+    MyResult,
+};
+use std::collections::HashMap;
+
+// SRP-6a, with N and g from challenge 33
+// See also http://srp.stanford.edu/design.html and RFC 5054
+const SALT_LEN: usize = 16;
+
+fn hash_to_int(parts: &[&[u8]]) -> BigUint {
+    BigUint::from_bytes_be(&sha256(&parts.concat()))
+}
+
+/**
+ * k = H(N | PAD(g)), where g is left-padded with zeros to the length of N
+ */
+pub fn multiplier(params: &DhParams) -> BigUint {
+    let n = params.p.to_bytes_be();
+    let g = params
+        .g
+        .to_bytes_be_padded(n.len())
+        .expect("g is smaller than N");
+    hash_to_int(&[&n, &g])
+}
+
+/**
+ * x = H(salt | password)
+ */
+pub fn private_key(salt: &[u8], password: &str) -> BigUint {
+    hash_to_int(&[salt, password.as_bytes()])
+}
+
+/**
+ * u = H(A | B)
+ */
+pub fn scrambler(a_public: &BigUint, b_public: &BigUint) -> BigUint {
+    hash_to_int(&[&a_public.to_bytes_be(), &b_public.to_bytes_be()])
+}
+
+/**
+ * K = H(S)
+ */
+pub fn session_key(secret: &BigUint) -> Vec<u8> {
+    sha256(&secret.to_bytes_be()).to_vec()
+}
+
+/**
+ * What the client sends to prove it has K: HMAC-SHA256(K, salt)
+ */
+pub fn proof(session_key: &[u8], salt: &[u8]) -> Vec<u8> {
+    hmac_sha256(session_key, salt).to_vec()
+}
+
+struct Verifier {
+    salt: Vec<u8>,
+    v: BigUint,
+}
+
+struct ServerSession {
+    salt: Vec<u8>,
+    key: Vec<u8>,
+}
+
+/**
+ * The salts and verifiers v = g^x of registered users, never their passwords, and
+ * the session key of each login in progress. Both servers keep their state here.
+ */
+struct Accounts {
+    params: DhParams,
+    users: HashMap<String, Verifier>,
+    sessions: HashMap<String, ServerSession>,
+}
+
+impl Accounts {
+    fn new() -> Self {
+        Accounts {
+            params: DhParams::nist(),
+            users: HashMap::new(),
+            sessions: HashMap::new(),
+        }
+    }
+
+    fn register(&mut self, email: &str, password: &str) {
+        let salt = random_bytes(SALT_LEN);
+        let x = private_key(&salt, password);
+        let v = self.params.g.modpow(&x, &self.params.p);
+        self.users.insert(email.to_string(), Verifier { salt, v });
+    }
+
+    fn user(&self, email: &str) -> MyResult<&Verifier> {
+        Ok(self.users.get(email).ok_or("unknown user")?)
+    }
+
+    fn start_session(&mut self, email: &str, secret: &BigUint) -> MyResult<Vec<u8>> {
+        let salt = self.user(email)?.salt.clone();
+        self.sessions.insert(
+            email.to_string(),
+            ServerSession {
+                salt: salt.clone(),
+                key: session_key(secret),
+            },
+        );
+        Ok(salt)
+    }
+
+    fn verify(&mut self, email: &str, client_proof: &[u8]) -> bool {
+        match self.sessions.remove(email) {
+            Some(session) => proof(&session.key, &session.salt) == client_proof,
+            None => false,
+        }
+    }
+}
+
+pub struct Server {
+    accounts: Accounts,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server {
+            accounts: Accounts::new(),
+        }
+    }
+
+    pub fn register(&mut self, email: &str, password: &str) {
+        self.accounts.register(email, password);
+    }
+
+    /**
+     * Receives I and A, and returns the salt and B = kv + g^b. A real server must
+     * refuse A = 0 mod N; this one doesn't (challenge 37).
+     */
+    pub fn start_login(&mut self, email: &str, a_public: &BigUint) -> MyResult<(Vec<u8>, BigUint)> {
+        let DhParams { p, g } = &self.accounts.params;
+        let v = &self.accounts.user(email)?.v;
+        let b = BigUint::random_below(p, &mut rand::thread_rng());
+        let kv = &multiplier(&self.accounts.params) * v;
+        let b_public = &(&kv + &g.modpow(&b, p)) % p;
+
+        let u = scrambler(a_public, &b_public);
+        let secret = (&(a_public * &v.modpow(&u, p)) % p).modpow(&b, p);
+        let salt = self.accounts.start_session(email, &secret)?;
+        Ok((salt, b_public))
+    }
+
+    pub fn verify(&mut self, email: &str, client_proof: &[u8]) -> bool {
+        self.accounts.verify(email, client_proof)
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Client {
+    params: DhParams,
+    email: String,
+    password: String,
+}
+
+impl Client {
+    pub fn new(email: &str, password: &str) -> Self {
+        Client {
+            params: DhParams::nist(),
+            email: email.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    /**
+     * S = (B - kg^x)^(a + ux), which matches the server's (Av^u)^b
+     */
+    pub fn login(&self, server: &mut Server) -> MyResult<bool> {
+        let DhParams { p, g } = &self.params;
+        let a = BigUint::random_below(p, &mut rand::thread_rng());
+        let a_public = g.modpow(&a, p);
+        let (salt, b_public) = server.start_login(&self.email, &a_public)?;
+
+        let u = scrambler(&a_public, &b_public);
+        let x = private_key(&salt, &self.password);
+        let k = multiplier(&self.params);
+        // add kN before subtracting so the base can't go negative
+        let kgx = &k * &g.modpow(&x, p);
+        let base = &(&(&b_public + &(&k * p)) - &kgx) % p;
+        let secret = base.modpow(&(&a + &(&u * &x)), p);
+        let client_proof = proof(&session_key(&secret), &salt);
+        Ok(server.verify(&self.email, &client_proof))
+    }
+}
+
+/**
+ * The server's side of the simplified protocol of challenge 38: the salt, B = g^b, and
+ * a random 128-bit u that no longer depends on A or B
+ */
+pub struct SimpleChallenge {
+    pub salt: Vec<u8>,
+    pub b_public: BigUint,
+    pub u: BigUint,
+}
+
+pub trait SimpleSrpServer {
+    fn start_login(&mut self, email: &str, a_public: &BigUint) -> MyResult<SimpleChallenge>;
+    fn verify(&mut self, email: &str, client_proof: &[u8]) -> bool;
+}
+
+pub struct SimpleServer {
+    accounts: Accounts,
+}
+
+impl SimpleServer {
+    pub fn new() -> Self {
+        SimpleServer {
+            accounts: Accounts::new(),
+        }
+    }
+
+    pub fn register(&mut self, email: &str, password: &str) {
+        self.accounts.register(email, password);
+    }
+}
+
+impl Default for SimpleServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimpleSrpServer for SimpleServer {
+    fn start_login(&mut self, email: &str, a_public: &BigUint) -> MyResult<SimpleChallenge> {
+        let DhParams { p, g } = &self.accounts.params;
+        let v = &self.accounts.user(email)?.v;
+        let b = BigUint::random_below(p, &mut rand::thread_rng());
+        let b_public = g.modpow(&b, p);
+        let u = BigUint::from_bytes_be(&random_bytes(16));
+
+        let secret = (&(a_public * &v.modpow(&u, p)) % p).modpow(&b, p);
+        let salt = self.accounts.start_session(email, &secret)?;
+        Ok(SimpleChallenge { salt, b_public, u })
+    }
+
+    fn verify(&mut self, email: &str, client_proof: &[u8]) -> bool {
+        self.accounts.verify(email, client_proof)
+    }
+}
+
+impl Client {
+    /**
+     * Logs in with the simplified protocol, where S = B^(a + ux)
+     */
+    pub fn simple_login(&self, server: &mut impl SimpleSrpServer) -> MyResult<bool> {
+        let DhParams { p, g } = &self.params;
+        let a = BigUint::random_below(p, &mut rand::thread_rng());
+        let a_public = g.modpow(&a, p);
+        let challenge = server.start_login(&self.email, &a_public)?;
+
+        let x = private_key(&challenge.salt, &self.password);
+        let secret = challenge.b_public.modpow(&(&a + &(&challenge.u * &x)), p);
+        let client_proof = proof(&session_key(&secret), &challenge.salt);
+        Ok(server.verify(&self.email, &client_proof))
+    }
+}
+
+#[test]
+fn test_multiplier() -> MyResult<()> {
+    assert_eq!(
+        multiplier(&DhParams::nist()),
+        BigUint::from_hex("04f99e0d74ef9882cd817dd7c59eaea1af70194e52baa52a99ec0fea0097e4e1")?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_login() -> MyResult<()> {
+    let mut server = Server::new();
+    server.register("alice@example.com", "hunter2");
+    assert!(Client::new("alice@example.com", "hunter2").login(&mut server)?);
+    assert!(!Client::new("alice@example.com", "hunter3").login(&mut server)?);
+    assert!(Client::new("bob@example.com", "hunter2")
+        .login(&mut server)
+        .is_err());
+
+    let mut server = SimpleServer::new();
+    server.register("alice@example.com", "hunter2");
+    assert!(Client::new("alice@example.com", "hunter2").simple_login(&mut server)?);
+    assert!(!Client::new("alice@example.com", "hunter3").simple_login(&mut server)?);
+    Ok(())
+}